@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{InstallerError, InstallerResult};
+
+/// Extracts a Spicetify release archive (`.zip` or `.tar.gz`) into
+/// `destination`, creating it if needed.
+pub(crate) fn extract(archive_path: &Path, destination: &Path) -> InstallerResult<()> {
+    fs::create_dir_all(destination)
+        .map_err(|e| InstallerError::io("Failed to create extraction directory", e))?;
+
+    let name = archive_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if is_zip(archive_path) {
+        extract_zip(archive_path, destination)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        extract_tarball(archive_path, destination)
+    } else {
+        Err(InstallerError::unsupported(format!(
+            "Unsupported archive format: {}",
+            archive_path.display()
+        ))
+        .with_remediation("Use a .zip or .tar.gz Spicetify release archive."))
+    }
+}
+
+/// Paths of the files in the archive, without extracting it.
+pub(crate) fn entry_names(archive_path: &Path) -> InstallerResult<Vec<String>> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| InstallerError::io("Failed to open archive", e))?;
+
+    if is_zip(archive_path) {
+        let archive = zip::ZipArchive::new(file).map_err(|e| {
+            InstallerError::invalid_data("Failed to read zip archive").with_detail(e)
+        })?;
+        return Ok(archive.file_names().map(str::to_string).collect());
+    }
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let entries = archive
+        .entries()
+        .map_err(|e| InstallerError::io("Failed to read archive", e))?;
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| InstallerError::io("Failed to read archive", e))?;
+        let path = entry
+            .path()
+            .map_err(|e| InstallerError::io("Failed to read archive", e))?;
+        names.push(path.to_string_lossy().to_string());
+    }
+    Ok(names)
+}
+
+fn is_zip(archive_path: &Path) -> bool {
+    archive_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+}
+
+fn extract_tarball(archive_path: &Path, destination: &Path) -> InstallerResult<()> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| InstallerError::io("Failed to open archive", e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive
+        .unpack(destination)
+        .map_err(|e| InstallerError::io("Failed to extract archive", e))
+}
+
+fn extract_zip(archive_path: &Path, destination: &Path) -> InstallerResult<()> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| InstallerError::io("Failed to open archive", e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| InstallerError::invalid_data("Failed to read zip archive").with_detail(e))?;
+    archive
+        .extract(destination)
+        .map_err(|e| InstallerError::invalid_data("Failed to extract zip archive").with_detail(e))
+}
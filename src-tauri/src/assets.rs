@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{InstallerError, InstallerResult};
+use crate::spicetify_config::SpicetifyPaths;
+
+const SECTION: &str = "AdditionalOptions";
+
+/// Whether an extension or custom app is turned on in `config-xpui.ini`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetStatus {
+    Enabled,
+    Disabled,
+    /// Listed in `config-xpui.ini` but not installed, so `spicetify apply`
+    /// will fail until it is disabled or reinstalled.
+    Missing,
+}
+
+/// Files or folders Spicetify turns on through a `|`-separated list in
+/// `[AdditionalOptions]`: extensions and custom apps.
+pub(crate) struct AssetKind {
+    /// Folder in the user data folder and next to the CLI, e.g. `Extensions`.
+    pub folder: &'static str,
+    /// Config list naming the enabled ones, e.g. `extensions`.
+    pub key: &'static str,
+    /// Used in messages, e.g. `Custom app`.
+    pub label: &'static str,
+    /// Whether an entry of `folder` is one of these assets.
+    pub is_asset: fn(&Path) -> bool,
+}
+
+/// What every kind of asset shares, before kind-specific details.
+pub(crate) struct Asset {
+    /// File or folder name, as written in the config.
+    pub name: String,
+    pub path: Option<PathBuf>,
+    /// Shipped with the CLI rather than the user's folder.
+    pub bundled: bool,
+    pub status: AssetStatus,
+}
+
+impl AssetKind {
+    /// Assets from the user's folder, then ones bundled with the CLI,
+    /// followed by enabled names with nothing installed. A user asset hides
+    /// a bundled one of the same name, as in Spicetify.
+    pub(crate) fn list(&self, paths: &SpicetifyPaths) -> InstallerResult<Vec<Asset>> {
+        let enabled = paths.load_config()?.get_list(SECTION, self.key);
+        let user_dir = self.user_dir(paths)?;
+
+        let mut assets: Vec<Asset> = Vec::new();
+        for dir in paths.asset_dirs(self.folder)? {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut found: Vec<Asset> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| (self.is_asset)(path))
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_string_lossy().to_string();
+                    Some(Asset {
+                        status: if enabled.contains(&name) {
+                            AssetStatus::Enabled
+                        } else {
+                            AssetStatus::Disabled
+                        },
+                        name,
+                        path: Some(path),
+                        bundled: dir != user_dir,
+                    })
+                })
+                .filter(|asset| !assets.iter().any(|known| known.name == asset.name))
+                .collect();
+            found.sort_by_key(|asset| asset.name.to_lowercase());
+            assets.extend(found);
+        }
+
+        for name in enabled {
+            if !assets.iter().any(|known| known.name == name) {
+                assets.push(Asset {
+                    name,
+                    path: None,
+                    bundled: false,
+                    status: AssetStatus::Missing,
+                });
+            }
+        }
+
+        Ok(assets)
+    }
+
+    /// Where installs go: `folder` in the user data folder.
+    pub(crate) fn user_dir(&self, paths: &SpicetifyPaths) -> InstallerResult<PathBuf> {
+        Ok(paths.user_data_dir()?.join(self.folder))
+    }
+
+    /// Adds `name` to or drops it from the config list, saving only when it
+    /// changed.
+    pub(crate) fn set_listed(
+        &self,
+        paths: &SpicetifyPaths,
+        name: &str,
+        enabled: bool,
+    ) -> InstallerResult<()> {
+        let mut config = paths.load_config()?;
+        if config.set_list_item(SECTION, self.key, name, enabled)? {
+            config.save()?;
+            eprintln!(
+                "{} {} {}",
+                if enabled { "Enabled" } else { "Disabled" },
+                self.label.to_lowercase(),
+                name
+            );
+        }
+        Ok(())
+    }
+
+    /// Deletes a user asset and drops it from the config list. Bundled
+    /// assets belong to the CLI and can only be disabled.
+    pub(crate) fn remove(&self, paths: &SpicetifyPaths, name: &str) -> InstallerResult<()> {
+        let asset = self
+            .list(paths)?
+            .into_iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| {
+                InstallerError::invalid_data(format!("{} {} is not installed", self.label, name))
+            })?;
+        if asset.bundled {
+            return Err(InstallerError::unsupported(format!(
+                "{} is bundled with Spicetify and cannot be removed",
+                name
+            ))
+            .with_remediation("Disable it instead."));
+        }
+
+        if let Some(path) = asset.path {
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            removed.map_err(|e| {
+                InstallerError::io(format!("Failed to remove {}", self.label.to_lowercase()), e)
+            })?;
+            eprintln!("Removed {} {:?}", self.label.to_lowercase(), path);
+        }
+        self.set_listed(paths, name, false)
+    }
+}
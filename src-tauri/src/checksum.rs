@@ -0,0 +1,132 @@
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::release_source::fetch_text;
+
+/// Asset names that list checksums for every file of a release.
+const CHECKSUM_LIST_NAMES: &[&str] = &[
+    "sha256sums",
+    "sha256sums.txt",
+    "checksums.txt",
+    "checksums.sha256",
+];
+
+/// SHA-256 of a file as lowercase hex.
+pub(crate) fn sha256_file(path: &Path) -> InstallerResult<String> {
+    let mut file =
+        File::open(path).map_err(|e| InstallerError::io("Failed to open file for hashing", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| InstallerError::io("Failed to read file for hashing", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Checks `path` against `expected` (hex, case-insensitive).
+pub(crate) fn verify_sha256(path: &Path, expected: &str) -> InstallerResult<()> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        return Ok(());
+    }
+
+    Err(InstallerError::new(
+        ErrorKind::ChecksumMismatch,
+        "Downloaded file does not match its published checksum",
+    )
+    .with_detail(format!(
+        "Expected SHA-256 {}, got {}",
+        expected.trim(),
+        actual
+    ))
+    .with_remediation("The download may be corrupted or tampered with. Try again later."))
+}
+
+/// Finds the published SHA-256 of `asset` in a GitHub release: the asset's
+/// own `digest` field first, then a `<name>.sha256` file, then a checksum
+/// list such as `SHA256SUMS`. Returns `None` when the release publishes none.
+pub(crate) async fn expected_sha256(
+    release: &serde_json::Value,
+    asset: &serde_json::Value,
+    user_agent: &str,
+) -> InstallerResult<Option<String>> {
+    if let Some(digest) = asset["digest"]
+        .as_str()
+        .and_then(|d| d.strip_prefix("sha256:"))
+    {
+        return Ok(Some(digest.to_lowercase()));
+    }
+
+    let asset_name = asset["name"].as_str().unwrap_or("");
+    let assets = release["assets"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    let per_file = [
+        format!("{}.sha256", asset_name),
+        format!("{}.sha256sum", asset_name),
+    ];
+    let checksum_asset = assets
+        .iter()
+        .find(|a| {
+            let name = a["name"].as_str().unwrap_or("").to_lowercase();
+            per_file
+                .iter()
+                .any(|candidate| candidate.to_lowercase() == name)
+        })
+        .or_else(|| {
+            assets.iter().find(|a| {
+                let name = a["name"].as_str().unwrap_or("").to_lowercase();
+                CHECKSUM_LIST_NAMES.contains(&name.as_str())
+            })
+        });
+
+    let Some(url) = checksum_asset.and_then(|a| a["browser_download_url"].as_str()) else {
+        return Ok(None);
+    };
+
+    let contents = fetch_text(url, user_agent).await?;
+    parse_checksum_file(&contents, asset_name)
+        .map(Some)
+        .ok_or_else(|| {
+            InstallerError::invalid_data(format!(
+                "No checksum for {} in the published checksum file",
+                asset_name
+            ))
+        })
+}
+
+/// Reads `sha256sum`-style output (`<hash>  <name>` or `<hash> *<name>`). A
+/// single bare hash is accepted for per-file checksum assets.
+fn parse_checksum_file(contents: &str, asset_name: &str) -> Option<String> {
+    let is_hash = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+
+    for line in contents.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(hash) = parts.next() else { continue };
+        if !is_hash(hash) {
+            continue;
+        }
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*') == asset_name => {
+                return Some(hash.to_lowercase())
+            }
+            None if contents.trim().lines().count() == 1 => return Some(hash.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    None
+}
@@ -0,0 +1,282 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use tauri::{App, AppHandle, Listener};
+use tauri_plugin_cli::{ArgData, CliExt, SubcommandMatches};
+
+use crate::commands::check_versions;
+use crate::download_cache;
+use crate::error::{ErrorKind, InstallerError};
+use crate::operations::Operation;
+use crate::process_runner::cancel_active_operation;
+
+pub const EXIT_SUCCESS: i32 = 0;
+/// The operation ran and failed.
+pub const EXIT_FAILURE: i32 = 1;
+/// Unknown subcommand or bad arguments.
+pub const EXIT_USAGE: i32 = 2;
+/// GitHub could not be reached or refused the request.
+pub const EXIT_NETWORK: i32 = 3;
+/// Spicetify is required but not installed.
+pub const EXIT_SPICETIFY_NOT_FOUND: i32 = 4;
+/// The user declined the confirmation prompt or pressed Ctrl+C.
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// Payload of the `operation_output` event.
+#[derive(Deserialize)]
+struct OutputEvent {
+    stream: String,
+    line: String,
+}
+
+/// Inspects the command line. Returns `true` when a subcommand was given and
+/// is now running headless; the process exits once it finishes.
+pub fn start(app: &App) -> bool {
+    let matches = match app.cli().matches() {
+        Ok(matches) => matches,
+        Err(e) => {
+            #[cfg(windows)]
+            attach_parent_console();
+            eprintln!("{}", e);
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+
+    print_help_and_exit(&matches.args);
+
+    let Some(subcommand) = matches.subcommand else {
+        return false;
+    };
+
+    let app_handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let code = run(app_handle, *subcommand).await;
+        let _ = io::stdout().flush();
+        std::process::exit(code);
+    });
+
+    true
+}
+
+/// `--help` and `--version` arrive as args whose value is the text to show.
+fn print_help_and_exit(args: &HashMap<String, ArgData>) {
+    for name in ["help", "version"] {
+        if let Some(text) = args.get(name).and_then(|arg| arg.value.as_str()) {
+            #[cfg(windows)]
+            attach_parent_console();
+            println!("{}", text);
+            std::process::exit(EXIT_SUCCESS);
+        }
+    }
+}
+
+/// Runs a CLI subcommand without the GUI and returns the process exit code.
+async fn run(app_handle: AppHandle, subcommand: SubcommandMatches) -> i32 {
+    #[cfg(windows)]
+    attach_parent_console();
+
+    let args = &subcommand.matches.args;
+    print_help_and_exit(args);
+
+    match subcommand.name.as_str() {
+        "install" => {
+            if let Some(archive_path) = value(args, "archive") {
+                let prompt = format!("Install Spicetify from {}?", archive_path);
+                return run_operation(
+                    app_handle,
+                    Operation::InstallFromArchive { archive_path },
+                    flag(args, "yes"),
+                    &prompt,
+                )
+                .await;
+            }
+
+            let version = value(args, "spicetify-version");
+            let prompt = match &version {
+                Some(version) => format!("Install Spicetify v{}?", version),
+                None => "Install the latest Spicetify?".to_string(),
+            };
+            run_operation(
+                app_handle,
+                Operation::Install { version },
+                flag(args, "yes"),
+                &prompt,
+            )
+            .await
+        }
+        "upgrade" => run_operation(app_handle, Operation::Upgrade, true, "").await,
+        "repair" => run_operation(app_handle, Operation::Repair, true, "").await,
+        "backup" => run_operation(app_handle, Operation::Backup, true, "").await,
+        "uninstall" => {
+            let operation = Operation::Uninstall {
+                keep_themes: flag(args, "keep-themes"),
+            };
+            run_operation(
+                app_handle,
+                operation,
+                flag(args, "yes"),
+                "Uninstall Spicetify and restore Spotify?",
+            )
+            .await
+        }
+        "check-updates" => check_updates(flag(args, "json")).await,
+        "clear-cache" => clear_cache(),
+        other => {
+            eprintln!("Unknown command: {}", other);
+            EXIT_USAGE
+        }
+    }
+}
+
+fn flag(args: &HashMap<String, ArgData>, name: &str) -> bool {
+    args.get(name)
+        .and_then(|arg| arg.value.as_bool())
+        .unwrap_or(false)
+}
+
+fn value(args: &HashMap<String, ArgData>, name: &str) -> Option<String> {
+    args.get(name)
+        .and_then(|arg| arg.value.as_str())
+        .map(str::to_string)
+}
+
+fn confirm(prompt: &str) -> bool {
+    print!("{} [y/N] ", prompt);
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+async fn run_operation(
+    app_handle: AppHandle,
+    operation: Operation,
+    confirmed: bool,
+    prompt: &str,
+) -> i32 {
+    if !confirmed && !confirm(prompt) {
+        println!("Aborted.");
+        return EXIT_CANCELLED;
+    }
+
+    let output_listener = app_handle.listen_any("operation_output", |event| {
+        if let Ok(line) = serde_json::from_str::<OutputEvent>(event.payload()) {
+            if line.stream == "stderr" {
+                eprintln!("{}", line.line);
+            } else {
+                println!("{}", line.line);
+            }
+        }
+    });
+    let progress_listener = app_handle.listen_any("progress_update", |event| {
+        if let Ok(progress) = event.payload().parse::<u32>() {
+            println!("[{:>3}%]", progress);
+        }
+    });
+
+    let ctrl_c = tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("Cancelling...");
+            cancel_active_operation();
+        }
+    });
+
+    let result = operation.run(app_handle.clone()).await;
+
+    ctrl_c.abort();
+    app_handle.unlisten(output_listener);
+    app_handle.unlisten(progress_listener);
+
+    match result {
+        Ok(_) => EXIT_SUCCESS,
+        Err(error) => report_error(&error),
+    }
+}
+
+async fn check_updates(json: bool) -> i32 {
+    match check_versions().await {
+        Ok(info) => {
+            if json {
+                match serde_json::to_string_pretty(&info) {
+                    Ok(text) => println!("{}", text),
+                    Err(e) => {
+                        eprintln!("Failed to serialize version info: {}", e);
+                        return EXIT_FAILURE;
+                    }
+                }
+            } else {
+                println!("Installer version: {}", info.installer_version);
+                if let Some(latest) = &info.latest_installer_version {
+                    println!("Latest installer:  {}", latest);
+                }
+                println!("Installer update:  {}", yes_no(info.has_installer_update));
+                println!(
+                    "Spicetify version: {}",
+                    info.spicetify_version.as_deref().unwrap_or("not installed")
+                );
+                if let Some(latest) = &info.latest_spicetify_version {
+                    println!("Latest Spicetify:  {}", latest);
+                }
+                println!("Spicetify update:  {}", yes_no(info.has_spicetify_update));
+            }
+            EXIT_SUCCESS
+        }
+        Err(error) => {
+            if json {
+                if let Ok(text) = serde_json::to_string_pretty(&error) {
+                    println!("{}", text);
+                }
+            }
+            report_error(&error)
+        }
+    }
+}
+
+fn clear_cache() -> i32 {
+    match download_cache::clear() {
+        Ok(summary) => {
+            println!("Cleared the download cache at {}", summary.path);
+            EXIT_SUCCESS
+        }
+        Err(error) => report_error(&error),
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "available"
+    } else {
+        "none"
+    }
+}
+
+fn report_error(error: &InstallerError) -> i32 {
+    eprintln!("Error: {}", error.message);
+    if let Some(detail) = &error.detail {
+        eprintln!("{}", detail);
+    }
+    if let Some(remediation) = &error.remediation {
+        eprintln!("Hint: {}", remediation);
+    }
+
+    match error.kind {
+        ErrorKind::Cancelled => EXIT_CANCELLED,
+        ErrorKind::Network | ErrorKind::RateLimited | ErrorKind::HttpStatus => EXIT_NETWORK,
+        ErrorKind::SpicetifyNotFound => EXIT_SPICETIFY_NOT_FOUND,
+        _ => EXIT_FAILURE,
+    }
+}
+
+/// Release builds use the GUI subsystem, so reattach to the terminal that
+/// launched us for stdout/stderr to be visible.
+#[cfg(windows)]
+fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
@@ -1,12 +1,34 @@
 use serde::{Deserialize, Serialize};
 use std::env;
-use std::fs;
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
 use std::process::Command;
-use tauri::{AppHandle, Emitter};
-use tokio::time::Duration;
-use crate::update_manager::{UpdateManager, UpdateInfo};
+use tauri::AppHandle;
+use crate::custom_apps::{self, CustomApp};
+use crate::diagnostics;
+use crate::download_cache::{self, CacheSummary};
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::extensions::{self, Extension};
+use crate::marketplace::{self, MarketplaceStatus};
+use crate::operation_history::{self, OperationRecord, OperationSummary};
+use crate::operations::Operation;
+use crate::process_runner::{
+    cancel_active_operation, hidden_command, run_powershell_script, run_program, ScriptOutput,
+    ScriptRun,
+};
+use crate::profiles::{self, SetupProfile};
+use crate::release_source::{newest_release, ReleaseChannel, ReleaseSource};
+use crate::settings;
+use crate::spicetify_config::{ConfigFile, SpicetifyConfig, SpicetifyPaths};
+#[cfg(target_os = "linux")]
+use crate::spicetify_install::{install_spicetify_linux, install_spicetify_linux_from_archive};
+use crate::spicetify_install::{
+    fetch_latest_spicetify_version, fetch_release_archive, inspect_local_archive,
+    list_spicetify_releases, resolve_target_version, InstallLog, SpicetifyRelease,
+};
+use crate::spicetify_probe::SpicetifyProbe;
+use crate::themes::{self, ThemesOverview};
+use crate::update_manager::{InstallerVersion, UpdateManager, UpdateInfo};
+use crate::version::is_newer;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VersionInfo {
@@ -22,45 +44,34 @@ pub struct VersionInfo {
     pub latest_installer_version: Option<String>,
     #[serde(rename = "latestInstallerUrl")]
     pub latest_installer_url: Option<String>,
+    #[serde(rename = "latestSpicetifyVersion")]
+    pub latest_spicetify_version: Option<String>,
+    #[serde(rename = "spicetifyProbe")]
+    pub spicetify_probe: SpicetifyProbe,
 }
 
 use std::sync::atomic::{AtomicBool, Ordering};
 static CHECKING_UPDATES: AtomicBool = AtomicBool::new(false);
 
-async fn check_github_release() -> Result<(String, String), String> {
-    println!("Checking for latest release on GitHub...");
-
-    let client = reqwest::Client::builder()
-        .user_agent("Spicetify-Installer")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get("https://api.github.com/repos/FIREPAWER07/SpicetifyInstaller/releases/latest")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch latest release: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "GitHub API returned status code: {}",
-            response.status()
-        ));
-    }
+async fn check_github_release() -> InstallerResult<(String, String)> {
+    eprintln!("Checking for latest release on GitHub...");
 
-    let release_info: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+    let release_info = newest_release(
+        &ReleaseSource::current().installer,
+        settings::current().release_channel,
+        "Spicetify-Installer",
+    )
+    .await?
+    .release;
 
     let tag_name = release_info["tag_name"]
         .as_str()
-        .ok_or("Missing tag_name in GitHub response")?
+        .ok_or_else(|| InstallerError::invalid_data("Missing tag_name in GitHub response"))?
         .to_string();
 
     let assets = release_info["assets"]
         .as_array()
-        .ok_or("Missing assets in GitHub response")?;
+        .ok_or_else(|| InstallerError::invalid_data("Missing assets in GitHub response"))?;
     let mut download_url = String::new();
 
     for asset in assets {
@@ -68,7 +79,7 @@ async fn check_github_release() -> Result<(String, String), String> {
         if name.ends_with(".exe") || name.contains("setup") {
             download_url = asset["browser_download_url"]
                 .as_str()
-                .ok_or("Missing download URL")?
+                .ok_or_else(|| InstallerError::invalid_data("Missing download URL"))?
                 .to_string();
             break;
         }
@@ -77,11 +88,11 @@ async fn check_github_release() -> Result<(String, String), String> {
     if download_url.is_empty() {
         download_url = release_info["html_url"]
             .as_str()
-            .ok_or("Missing release URL")?
+            .ok_or_else(|| InstallerError::invalid_data("Missing release URL"))?
             .to_string();
     }
 
-    println!(
+    eprintln!(
         "Found latest version: {} with URL: {}",
         tag_name, download_url
     );
@@ -89,105 +100,19 @@ async fn check_github_release() -> Result<(String, String), String> {
 }
 
 #[tauri::command]
-pub async fn execute_powershell_command(
-    command: String,
+pub async fn execute_operation(
+    operation: Operation,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    println!("Executing PowerShell command: {}", command);
-
-    if command.contains("spicetify-cli/master/install.ps1") {
-        return install_spicetify_direct(app_handle).await;
-    }
-
-    if command.contains("spicetify restore") && command.contains("Remove-Item") {
-        return execute_uninstall_command(app_handle).await;
-    } else if command == "spicetify restore backup apply" {
-        return execute_repair_command(app_handle).await;
-    } else if command == "spicetify backup" {
-        return execute_backup_command(app_handle).await;
-    }
-
-    let output = execute_with_progress(command, app_handle).await?;
-    Ok(output)
-}
-
-async fn execute_with_progress(command: String, app_handle: AppHandle) -> Result<String, String> {
-    let app_handle_clone = app_handle.clone();
-
-    app_handle.emit("progress_update", 0).unwrap();
-
-    let temp_dir = env::temp_dir();
-    let script_path = temp_dir.join("spicetify_command.ps1");
-
-    let script_content = format!(
-        r#"
-$ErrorActionPreference = 'Stop'
-try {{
-    {}
-    Write-Host "Command executed successfully."
-}} catch {{
-    Write-Host "Error: $_"
-    exit 1
-}}
-"#,
-        command
-    );
-
-    fs::write(&script_path, script_content)
-        .map_err(|e| format!("Failed to write command script: {}", e))?;
-
-    let progress_handle = tokio::spawn(async move {
-        let total_steps = 10;
-        for step in 1..=total_steps {
-            let progress = (step as f32 / total_steps as f32) * 100.0;
-            app_handle_clone
-                .emit("progress_update", progress as u32)
-                .unwrap();
-            tokio::time::sleep(Duration::from_millis(300)).await;
-        }
-    });
-
-    let output = Command::new("powershell")
-        .args(&[
-            "-ExecutionPolicy",
-            "Bypass",
-            "-File",
-            &script_path.to_string_lossy(),
-        ])
-        .creation_flags(0x08000000)
-        .output()
-        .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-    let _ = fs::remove_file(script_path);
-    let _ = progress_handle.await;
-
-    app_handle.emit("progress_update", 100).unwrap();
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    println!("Command output: {}", stdout);
-    if !stderr.is_empty() {
-        println!("Command error: {}", stderr);
-    }
-
-    if output.status.success() {
-        Ok(format!("{}\n{}", stdout, stderr))
-    } else {
-        Err(format!(
-            "Command failed with status code {}: {}",
-            output.status, stderr
-        ))
-    }
+) -> InstallerResult<String> {
+    operation.run(app_handle).await
 }
 
-async fn execute_uninstall_command(app_handle: AppHandle) -> Result<String, String> {
-    app_handle.emit("progress_update", 10).unwrap();
-
-    let temp_dir = env::temp_dir();
-    let script_path = temp_dir.join("spicetify_uninstall.ps1");
-
+pub(crate) async fn execute_uninstall_command(
+    app_handle: AppHandle,
+    keep_themes: bool,
+) -> InstallerResult<String> {
     let script_content = r#"
+param([switch]$KeepThemes)
 $ErrorActionPreference = 'Continue'
 Write-Host "Step 1: Restoring Spotify to original state..."
 try {
@@ -199,8 +124,13 @@ try {
 
 Write-Host "`nStep 2: Removing Spicetify from AppData folder..."
 if (Test-Path "$env:APPDATA\spicetify") {
-    Remove-Item -Path "$env:APPDATA\spicetify" -Recurse -Force -ErrorAction SilentlyContinue
-    Write-Host "Removed Spicetify from AppData folder."
+    if ($KeepThemes) {
+        Get-ChildItem -Path "$env:APPDATA\spicetify" -Exclude "Themes" | Remove-Item -Recurse -Force -ErrorAction SilentlyContinue
+        Write-Host "Removed Spicetify from AppData folder (kept Themes)."
+    } else {
+        Remove-Item -Path "$env:APPDATA\spicetify" -Recurse -Force -ErrorAction SilentlyContinue
+        Write-Host "Removed Spicetify from AppData folder."
+    }
 } else {
     Write-Host "AppData\spicetify folder not found."
 }
@@ -216,51 +146,31 @@ if (Test-Path "$env:LOCALAPPDATA\spicetify") {
 Write-Host "`nSpicetify has been completely uninstalled from your system."
 "#;
 
-    fs::write(&script_path, script_content)
-        .map_err(|e| format!("Failed to write uninstall script: {}", e))?;
+    let mut args = Vec::new();
+    if keep_themes {
+        args.push("-KeepThemes".to_string());
+    }
 
-    let app_handle_clone = app_handle.clone();
-    let progress_handle = tokio::spawn(async move {
-        let progress_steps = vec![20, 40, 60, 80, 90];
-        for progress in progress_steps {
-            tokio::time::sleep(Duration::from_millis(500)).await;
-            app_handle_clone.emit("progress_update", progress).unwrap();
-        }
-    });
-
-    let output = Command::new("powershell")
-        .args(&[
-            "-ExecutionPolicy",
-            "Bypass",
-            "-File",
-            &script_path.to_string_lossy(),
-        ])
-        .creation_flags(0x08000000)
-        .output()
-        .map_err(|e| format!("Failed to execute uninstall script: {}", e))?;
-
-    let _ = fs::remove_file(script_path);
-    let _ = progress_handle.await;
-
-    app_handle.emit("progress_update", 100).unwrap();
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    println!("Uninstall output: {}", stdout);
-    if !stderr.is_empty() {
-        println!("Uninstall error: {}", stderr);
+    let output = run_powershell_script(
+        &app_handle,
+        ScriptRun {
+            name: "uninstall",
+            script: script_content,
+            args,
+            total_steps: 3,
+            temp_files: Vec::new(),
+        },
+    )
+    .await?;
+
+    if !output.stderr.is_empty() {
+        eprintln!("Uninstall error: {}", output.stderr);
     }
 
-    Ok(stdout)
+    Ok(output.stdout)
 }
 
-async fn execute_repair_command(app_handle: AppHandle) -> Result<String, String> {
-    app_handle.emit("progress_update", 10).unwrap();
-
-    let temp_dir = env::temp_dir();
-    let script_path = temp_dir.join("spicetify_repair.ps1");
-
+pub(crate) async fn execute_repair_command(app_handle: AppHandle) -> InstallerResult<String> {
     let script_content = r#"
 $ErrorActionPreference = 'Stop'
 Write-Host "Step 1: Restoring Spotify to original state..."
@@ -293,117 +203,188 @@ try {
 Write-Host "`nRepair process completed successfully!"
 "#;
 
-    fs::write(&script_path, script_content)
-        .map_err(|e| format!("Failed to write repair script: {}", e))?;
-
-    let app_handle_clone = app_handle.clone();
-    let progress_handle = tokio::spawn(async move {
-        let progress_steps = vec![20, 30, 50, 70, 90];
-        for progress in progress_steps {
-            tokio::time::sleep(Duration::from_millis(500)).await;
-            app_handle_clone.emit("progress_update", progress).unwrap();
-        }
-    });
-
-    let output = Command::new("powershell")
-        .args(&[
-            "-ExecutionPolicy",
-            "Bypass",
-            "-File",
-            &script_path.to_string_lossy(),
-        ])
-        .creation_flags(0x08000000)
-        .output()
-        .map_err(|e| format!("Failed to execute repair script: {}", e))?;
-
-    let _ = fs::remove_file(script_path);
-    let _ = progress_handle.await;
-
-    app_handle.emit("progress_update", 100).unwrap();
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    println!("Repair output: {}", stdout);
-    if !stderr.is_empty() {
-        println!("Repair error: {}", stderr);
-    }
-
-    if output.status.success() {
-        Ok(stdout)
-    } else {
-        Err(format!("Repair failed: {}", stderr))
-    }
+    run_spicetify_script(&app_handle, "repair", "Repair", script_content, 3).await
 }
 
-async fn execute_backup_command(app_handle: AppHandle) -> Result<String, String> {
-    app_handle.emit("progress_update", 10).unwrap();
+/// `binary` is the CLI to run, found with the probe when `None`.
+pub(crate) async fn execute_backup_command(
+    app_handle: AppHandle,
+    binary: Option<PathBuf>,
+) -> InstallerResult<String> {
+    run_spicetify(&app_handle, "backup", "Backup", binary).await
+}
 
-    let temp_dir = env::temp_dir();
-    let script_path = temp_dir.join("spicetify_backup.ps1");
+/// `binary` is the CLI to run, found with the probe when `None`.
+pub(crate) async fn execute_apply_command(
+    app_handle: AppHandle,
+    binary: Option<PathBuf>,
+) -> InstallerResult<String> {
+    run_spicetify(&app_handle, "apply", "Apply", binary).await
+}
 
+pub(crate) async fn execute_restore_command(app_handle: AppHandle) -> InstallerResult<String> {
     let script_content = r#"
 $ErrorActionPreference = 'Stop'
-Write-Host "Creating backup of Spotify installation..."
+Write-Host "Step 1: Restoring Spotify to original state..."
 try {
-    spicetify backup
-    Write-Host "Backup created successfully."
+    spicetify restore
+    Write-Host "Spotify has been restored to its original state."
 } catch {
     Write-Host "Error: $($_.Exception.Message)"
     exit 1
 }
 
-Write-Host "`nBackup process completed successfully!"
+Write-Host "`nRestore process completed successfully!"
 "#;
 
-    fs::write(&script_path, script_content)
-        .map_err(|e| format!("Failed to write backup script: {}", e))?;
+    run_spicetify_script(&app_handle, "restore", "Restore", script_content, 1).await
+}
+
+/// Runs `spicetify <command>` without a shell, so it works where PowerShell
+/// is missing and right after an install, before the CLI is on `PATH`.
+async fn run_spicetify(
+    app_handle: &AppHandle,
+    command: &str,
+    label: &str,
+    binary: Option<PathBuf>,
+) -> InstallerResult<String> {
+    let binary = binary
+        .or_else(|| SpicetifyProbe::run().path.map(PathBuf::from))
+        .ok_or_else(|| {
+            InstallerError::new(ErrorKind::SpicetifyNotFound, "Spicetify is not installed")
+                .with_remediation("Install Spicetify first.")
+        })?;
+
+    let output = run_program(app_handle, command, &binary, &[command]).await?;
+    command_result(label, output)
+}
+
+/// Runs a `spicetify` maintenance script and fails with its stderr when the
+/// script exits unsuccessfully.
+async fn run_spicetify_script(
+    app_handle: &AppHandle,
+    name: &str,
+    label: &str,
+    script: &str,
+    total_steps: u32,
+) -> InstallerResult<String> {
+    let output = run_powershell_script(
+        app_handle,
+        ScriptRun {
+            name,
+            script,
+            args: Vec::new(),
+            total_steps,
+            temp_files: Vec::new(),
+        },
+    )
+    .await?;
+
+    command_result(label, output)
+}
 
-    let app_handle_clone = app_handle.clone();
-    let progress_handle = tokio::spawn(async move {
-        let progress_steps = vec![30, 50, 70, 90];
-        for progress in progress_steps {
-            tokio::time::sleep(Duration::from_millis(400)).await;
-            app_handle_clone.emit("progress_update", progress).unwrap();
+/// Fails with the output of a `spicetify` run that exited unsuccessfully.
+fn command_result(label: &str, output: ScriptOutput) -> InstallerResult<String> {
+    if output.success {
+        Ok(output.stdout)
+    } else {
+        Err(InstallerError::process_failed(
+            format!("{} failed ({})", label, output.status),
+            &format!("{}\n{}", output.stderr, output.stdout),
+        ))
+    }
+}
+
+pub(crate) async fn install_spicetify_direct(
+    app_handle: AppHandle,
+    version: Option<String>,
+) -> InstallerResult<String> {
+    #[cfg(target_os = "linux")]
+    let output = install_spicetify_linux(app_handle.clone(), version).await?;
+
+    #[cfg(not(target_os = "linux"))]
+    let output = install_spicetify_windows(app_handle.clone(), version).await?;
+
+    // Spicetify itself is installed at this point, so a Marketplace failure
+    // is reported rather than failing the whole install
+    match marketplace::install(&app_handle).await {
+        Ok(marketplace_output) => Ok(format!("{}\n{}", output, marketplace_output)),
+        Err(e) if e.kind == ErrorKind::Cancelled => Err(e),
+        Err(e) => {
+            let message = format!(
+                "Marketplace was not installed: {}\nUse Marketplace > Install to try again.",
+                e
+            );
+            InstallLog::new(&app_handle).line(&message);
+            Ok(format!("{}\n{}", output, message))
         }
-    });
-
-    let output = Command::new("powershell")
-        .args(&[
-            "-ExecutionPolicy",
-            "Bypass",
-            "-File",
-            &script_path.to_string_lossy(),
-        ])
-        .creation_flags(0x08000000)
-        .output()
-        .map_err(|e| format!("Failed to execute backup script: {}", e))?;
-
-    let _ = fs::remove_file(script_path);
-    let _ = progress_handle.await;
-
-    app_handle.emit("progress_update", 100).unwrap();
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    println!("Backup output: {}", stdout);
-    if !stderr.is_empty() {
-        println!("Backup error: {}", stderr);
     }
+}
 
-    if output.status.success() {
-        Ok(stdout)
-    } else {
-        Err(format!("Backup failed: {}", stderr))
+/// Installs from a local release archive, for machines without internet.
+pub(crate) async fn install_spicetify_from_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+) -> InstallerResult<String> {
+    let archive_path = PathBuf::from(archive_path);
+
+    #[cfg(target_os = "linux")]
+    return install_spicetify_linux_from_archive(app_handle, archive_path).await;
+
+    #[cfg(not(target_os = "linux"))]
+    install_spicetify_windows_from_archive(app_handle, archive_path).await
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+async fn install_spicetify_windows(
+    app_handle: AppHandle,
+    version: Option<String>,
+) -> InstallerResult<String> {
+    let target_version = resolve_target_version(version.as_deref()).await?;
+    let archive = fetch_release_archive(&target_version).await?;
+    if archive.cached {
+        eprintln!("Installing Spicetify v{} from the download cache", target_version);
     }
+
+    run_install_script(
+        app_handle,
+        vec![
+            "-TargetVersion".to_string(),
+            target_version,
+            "-ArchivePath".to_string(),
+            archive.path.to_string_lossy().to_string(),
+        ],
+    )
+    .await
 }
 
-async fn install_spicetify_direct(app_handle: AppHandle) -> Result<String, String> {
-    let temp_dir = env::temp_dir();
-    let install_script_path = temp_dir.join("spicetify_direct_install.ps1");
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+async fn install_spicetify_windows_from_archive(
+    app_handle: AppHandle,
+    archive_path: PathBuf,
+) -> InstallerResult<String> {
+    let target_version = inspect_local_archive(&archive_path)?;
+
+    run_install_script(
+        app_handle,
+        vec![
+            "-TargetVersion".to_string(),
+            target_version,
+            "-ArchivePath".to_string(),
+            archive_path.to_string_lossy().to_string(),
+        ],
+    )
+    .await
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+async fn run_install_script(app_handle: AppHandle, args: Vec<String>) -> InstallerResult<String> {
 
     let install_script = r#"
+param(
+  [Parameter(Mandatory = $true)][string]$TargetVersion,
+  [Parameter(Mandatory = $true)][string]$ArchivePath
+)
 $ErrorActionPreference = 'Stop'
 [Net.ServicePointManager]::SecurityProtocol = [Net.SecurityProtocolType]::Tls12
 
@@ -462,47 +443,14 @@ function Add-SpicetifyToPath {
 }
 
 function Get-Spicetify {
-  if ($env:PROCESSOR_ARCHITECTURE -eq 'AMD64') {
-    $architecture = 'x64'
-  }
-  elseif ($env:PROCESSOR_ARCHITECTURE -eq 'ARM64') {
-    $architecture = 'arm64'
-  }
-  else {
-    $architecture = 'x32'
-  }
-  
-  Write-Host 'Fetching the latest Spicetify version...' -NoNewline
-  try {
-    $latestRelease = Invoke-RestMethod -Uri 'https://api.github.com/repos/spicetify/cli/releases/latest'
-    $targetVersion = $latestRelease.tag_name -replace 'v', ''
-    Write-Success "Found version $targetVersion"
-  }
-  catch {
-    Write-Unsuccess
-    Write-Host "Failed to fetch latest version: $_" -ForegroundColor Red
-    $targetVersion = "2.16.2"
-    Write-Host "Using fallback version $targetVersion" -ForegroundColor Yellow
-  }
-  
+  $targetVersion = $TargetVersion
   $archivePath = [System.IO.Path]::Combine([System.IO.Path]::GetTempPath(), "spicetify.zip")
-  
-  Write-Host "Downloading Spicetify v$targetVersion for $architecture..." -NoNewline
-  try {
-    $Parameters = @{
-      Uri            = "https://github.com/spicetify/cli/releases/download/v$targetVersion/spicetify-$targetVersion-windows-$architecture.zip"
-      UseBasicParsing = $true
-      OutFile        = $archivePath
-    }
-    Invoke-WebRequest @Parameters
-    Write-Success
-    return $archivePath
-  }
-  catch {
-    Write-Unsuccess
-    Write-Host "Download failed: $_" -ForegroundColor Red
-    throw
-  }
+
+  Write-Host "Step 2: Preparing Spicetify v$targetVersion..."
+  Write-Host "Copying $ArchivePath..." -NoNewline
+  Copy-Item -Path $ArchivePath -Destination $archivePath -Force
+  Write-Success
+  return $archivePath
 }
 
 function Install-Spicetify {
@@ -517,10 +465,12 @@ function Install-Spicetify {
   try {
     $archivePath = Get-Spicetify
     
+    Write-Host "Step 3: Extracting Spicetify..."
     Write-Host 'Extracting Spicetify...' -NoNewline
     Expand-Archive -Path $archivePath -DestinationPath $spicetifyFolderPath -Force
     Write-Success
     
+    Write-Host "Step 4: Updating PATH..."
     Add-SpicetifyToPath
     
     Remove-Item -Path $archivePath -Force -ErrorAction 'SilentlyContinue'
@@ -545,30 +495,6 @@ function Install-Spicetify {
   }
 }
 
-function Install-SpicetifyMarketplace {
-  Write-Host "Installing Spicetify Marketplace..." -ForegroundColor Cyan
-  try {
-    $Parameters = @{
-      Uri             = 'https://raw.githubusercontent.com/spicetify/spicetify-marketplace/main/resources/install.ps1'
-      UseBasicParsing = $true
-    }
-    $marketplaceScript = (Invoke-WebRequest @Parameters).Content
-    
-    $tempScriptPath = [System.IO.Path]::Combine([System.IO.Path]::GetTempPath(), "marketplace-install.ps1")
-    Set-Content -Path $tempScriptPath -Value $marketplaceScript
-    
-    & $tempScriptPath
-    
-    Remove-Item -Path $tempScriptPath -Force -ErrorAction SilentlyContinue
-    
-    Write-Host "Marketplace installation completed" -ForegroundColor Green
-  }
-  catch {
-    Write-Host "Marketplace installation failed: $_" -ForegroundColor Red
-    Write-Host "You can install the marketplace manually later." -ForegroundColor Yellow
-  }
-}
-
 function Test-SpicetifyCommand {
   Write-Host "Testing Spicetify command..." -NoNewline
   try {
@@ -600,6 +526,7 @@ function Test-SpicetifyCommand {
 
 #region Main
 try {
+  Write-Host "Step 1: Preparing installation..."
   if (-not (Test-Admin)) {
     Write-Warning "The script is being run as administrator. This can result in problems with the installation process."
     Write-Success "Proceeding anyway"
@@ -619,12 +546,9 @@ try {
   
   $installPath = Install-Spicetify
   
+  Write-Host "Step 5: Verifying Spicetify command..."
   $commandWorks = Test-SpicetifyCommand
   
-  if ($commandWorks) {
-    Install-SpicetifyMarketplace
-  }
-  
   Write-Host ""
   Write-Host "== Installation Summary ==" -ForegroundColor Cyan
   Write-Host "Installation directory: $installPath"
@@ -663,269 +587,105 @@ catch {
 #endregion Main
     "#;
 
-    fs::write(&install_script_path, install_script)
-        .map_err(|e| format!("Failed to write installation script: {}", e))?;
-
-    app_handle.emit("progress_update", 0).unwrap();
-
-    let app_handle_clone = app_handle.clone();
-    let progress_handle = tokio::spawn(async move {
-        let progress_steps = vec![10, 15, 20, 30, 50, 70, 80, 90, 95];
-
-        for progress in progress_steps {
-            tokio::time::sleep(Duration::from_millis(800)).await;
-            app_handle_clone.emit("progress_update", progress).unwrap();
-        }
-    });
-
-    let output = Command::new("powershell")
-        .args(&[
-            "-ExecutionPolicy",
-            "Bypass",
-            "-File",
-            &install_script_path.to_string_lossy(),
-        ])
-        .creation_flags(0x08000000)
-        .output()
-        .map_err(|e| format!("Failed to execute installation script: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    let _ = fs::remove_file(install_script_path);
-    let _ = progress_handle.await;
-
-    app_handle.emit("progress_update", 100).unwrap();
-
-    if output.status.success() {
+    let output = run_powershell_script(
+        &app_handle,
+        ScriptRun {
+            name: "direct_install",
+            script: install_script,
+            args,
+            total_steps: 5,
+            temp_files: vec![env::temp_dir().join("spicetify.zip")],
+        },
+    )
+    .await?;
+
+    if output.success {
         Ok(format!(
             "Spicetify installation completed successfully.\n{}\n{}",
-            stdout, stderr
+            output.stdout, output.stderr
         ))
     } else {
-        Err(format!(
-            "Spicetify installation failed: {}\n{}",
-            stderr, stdout
+        Err(InstallerError::process_failed(
+            format!("Spicetify installation failed ({})", output.status),
+            &format!("{}\n{}", output.stderr, output.stdout),
         ))
     }
 }
 
 #[tauri::command]
-pub async fn check_versions() -> Result<VersionInfo, String> {
+pub async fn cancel_operation() -> InstallerResult<bool> {
+    Ok(cancel_active_operation())
+}
+
+#[tauri::command]
+pub async fn get_operation_history() -> InstallerResult<Vec<OperationSummary>> {
+    operation_history::summaries()
+}
+
+#[tauri::command]
+pub async fn get_operation_transcript(id: String) -> InstallerResult<OperationRecord> {
+    operation_history::record(&id)
+}
+
+#[tauri::command]
+pub async fn check_versions() -> InstallerResult<VersionInfo> {
     if CHECKING_UPDATES
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_err()
     {
-        return Err("Already checking for updates".to_string());
+        return Err(InstallerError::busy("Already checking for updates"));
     }
 
     let installer_version = env!("CARGO_PKG_VERSION").to_string();
-    let mut diagnostic_info = String::new();
-
-    println!("Checking if Spicetify directory exists...");
-    diagnostic_info.push_str("Checking Spicetify directories:\n");
-
-    let localappdata_path = match env::var("LOCALAPPDATA") {
-        Ok(path) => path,
-        Err(_) => {
-            diagnostic_info.push_str("- Failed to get LOCALAPPDATA environment variable\n");
-            String::new()
-        }
-    };
-
-    let spicetify_path = format!("{}\\spicetify", localappdata_path);
-    let spicetify_exe_path = format!("{}\\spicetify.exe", spicetify_path);
-
-    let dir_exists = std::path::Path::new(&spicetify_path).exists();
-    let exe_exists = std::path::Path::new(&spicetify_exe_path).exists();
-
-    diagnostic_info.push_str(&format!(
-        "- Spicetify directory exists at {}: {}\n",
-        spicetify_path, dir_exists
-    ));
-    diagnostic_info.push_str(&format!(
-        "- Spicetify executable exists at {}: {}\n",
-        spicetify_exe_path, exe_exists
-    ));
-
-    let mut spicetify_version = None;
-
-    if exe_exists {
-        println!("Trying direct Spicetify executable check...");
-        diagnostic_info.push_str("\nTrying direct executable check:\n");
-
-        let direct_output = Command::new(&spicetify_exe_path)
-            .creation_flags(0x08000000)
-            .args(&["-v"])
-            .output();
-
-        match direct_output {
-            Ok(output) => {
-                if output.status.success() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    diagnostic_info
-                        .push_str(&format!("- Direct executable check result: {}\n", version));
-                    if !version.is_empty() {
-                        spicetify_version = Some(version);
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                    diagnostic_info.push_str(&format!(
-                        "- Direct executable check failed with status: {}\n",
-                        output.status
-                    ));
-                    if !stderr.is_empty() {
-                        diagnostic_info.push_str(&format!("- Error: {}\n", stderr));
-                    }
-                }
-            }
-            Err(e) => {
-                diagnostic_info.push_str(&format!("- Direct executable check error: {}\n", e));
-            }
-        }
-    }
-
-    if spicetify_version.is_none() {
-        println!("Checking Spicetify version using CMD...");
-        diagnostic_info.push_str("\nTrying CMD check:\n");
-
-        let cmd_output = Command::new("cmd")
-            .creation_flags(0x08000000)
-            .args(&["/c", "spicetify -v"])
-            .output();
-
-        match cmd_output {
-            Ok(output) => {
-                if output.status.success() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    diagnostic_info.push_str(&format!("- CMD check result: {}\n", version));
-                    if !version.is_empty() {
-                        spicetify_version = Some(version);
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                    diagnostic_info.push_str(&format!(
-                        "- CMD check failed with status: {}\n",
-                        output.status
-                    ));
-                    if !stderr.is_empty() {
-                        diagnostic_info.push_str(&format!("- Error: {}\n", stderr));
-                    }
-                }
-            }
-            Err(e) => {
-                diagnostic_info.push_str(&format!("- CMD check error: {}\n", e));
-            }
-        }
+    eprintln!("Probing for Spicetify...");
+    let spicetify_probe = SpicetifyProbe::run();
+    for attempt in &spicetify_probe.attempts {
+        eprintln!(
+            "- {:?} {}: {}",
+            attempt.method,
+            attempt.path,
+            attempt.error.as_deref().unwrap_or("ok")
+        );
     }
+    let spicetify_version = spicetify_probe.version.clone();
 
-    if spicetify_version.is_none() {
-        println!("Trying fallback PowerShell approach...");
-        diagnostic_info.push_str("\nTrying PowerShell check:\n");
-
-        let ps_output = Command::new("powershell")
-            .creation_flags(0x08000000)
-            .args(&[
-                "-NoProfile",
-                "-NonInteractive",
-                "-ExecutionPolicy",
-                "Bypass",
-                "-Command",
-                "spicetify -v",
-            ])
-            .output();
-
-        match ps_output {
-            Ok(output) => {
-                if output.status.success() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    diagnostic_info.push_str(&format!("- PowerShell check result: {}\n", version));
-                    if !version.is_empty() {
-                        spicetify_version = Some(version);
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                    diagnostic_info.push_str(&format!(
-                        "- PowerShell check failed with status: {}\n",
-                        output.status
-                    ));
-                    if !stderr.is_empty() {
-                        diagnostic_info.push_str(&format!("- Error: {}\n", stderr));
-                    }
-                }
-            }
-            Err(e) => {
-                diagnostic_info.push_str(&format!("- PowerShell check error: {}\n", e));
-            }
+    let latest_spicetify_version = match fetch_latest_spicetify_version().await {
+        Ok(version) => Some(version),
+        Err(e) => {
+            eprintln!("Error checking the latest Spicetify release: {}", e);
+            None
         }
-    }
+    };
 
-    let has_spicetify_update = if let Some(current_version) = &spicetify_version {
-        !current_version.starts_with("2.")
-    } else {
-        false
+    let has_spicetify_update = match (&spicetify_version, &latest_spicetify_version) {
+        (Some(current), Some(latest)) => is_newer(latest, current),
+        _ => false,
     };
 
     let (latest_version, download_url) = match check_github_release().await {
         Ok((v, url)) => (Some(v), Some(url)),
         Err(e) => {
-            println!("Error checking GitHub: {}", e);
-            diagnostic_info.push_str(&format!("\nGitHub check error: {}\n", e));
+            eprintln!("Error checking GitHub: {}", e);
             (None, None)
         }
     };
 
     let has_installer_update = if let Some(latest) = &latest_version {
-        println!(
+        eprintln!(
             "Comparing versions: current={}, latest={}",
             installer_version, latest
         );
 
-        if latest != &installer_version {
-            let current_parts: Vec<&str> =
-                installer_version.split(|c: char| !c.is_digit(10)).collect();
-            let latest_parts: Vec<&str> = latest.split(|c: char| !c.is_digit(10)).collect();
-
-            let mut is_newer = false;
-
-            for i in 0..std::cmp::min(current_parts.len(), latest_parts.len()) {
-                if !current_parts[i].is_empty() && !latest_parts[i].is_empty() {
-                    let current_num = current_parts[i].parse::<i32>().unwrap_or(0);
-                    let latest_num = latest_parts[i].parse::<i32>().unwrap_or(0);
-
-                    if latest_num != current_num {
-                        is_newer = latest_num > current_num;
-                        break;
-                    }
-                }
-            }
-
-            if !is_newer && latest_parts.len() != current_parts.len() {
-                is_newer = latest_parts.len() > current_parts.len();
-            }
-
-            if !is_newer {
-                is_newer = latest > &installer_version;
-            }
-
-            is_newer
-        } else {
-            false
-        }
+        is_newer(latest, &installer_version)
     } else {
         false
     };
 
-    println!(
+    eprintln!(
         "Final version info: installer={}, spicetify={:?}, has_update={}",
         installer_version, spicetify_version, has_spicetify_update
     );
 
-    diagnostic_info.push_str(&format!("\nFinal version info: installer={}, spicetify={:?}, has_update={}, diagnostic_complete=true",
-        installer_version, spicetify_version, has_spicetify_update));
-
-    println!("Diagnostic info:\n{}", diagnostic_info);
-
     CHECKING_UPDATES.store(false, Ordering::SeqCst);
 
     Ok(VersionInfo {
@@ -935,20 +695,195 @@ pub async fn check_versions() -> Result<VersionInfo, String> {
         has_spicetify_update,
         latest_installer_version: latest_version,
         latest_installer_url: download_url,
+        latest_spicetify_version,
+        spicetify_probe,
     })
 }
 
 #[tauri::command]
-pub async fn download_update(_app_handle: AppHandle) -> Result<(), String> {
+pub async fn list_themes() -> InstallerResult<ThemesOverview> {
+    themes::list(&SpicetifyPaths::resolve()?)
+}
+
+/// Switches to `theme` and, unless `apply` is `false`, runs `spicetify apply`.
+#[tauri::command]
+pub async fn set_theme(
+    app_handle: AppHandle,
+    theme: String,
+    color_scheme: Option<String>,
+    apply: Option<bool>,
+) -> InstallerResult<ThemesOverview> {
+    let overview = themes::select(&SpicetifyPaths::resolve()?, &theme, color_scheme.as_deref())?;
+
+    if apply.unwrap_or(true) {
+        Operation::Apply.run(app_handle).await?;
+    }
+
+    Ok(overview)
+}
+
+#[tauri::command]
+pub async fn list_extensions() -> InstallerResult<Vec<Extension>> {
+    extensions::list(&SpicetifyPaths::resolve()?)
+}
+
+/// Toggles the extension file `name` in the `extensions` list. An
+/// extension whose file is gone can still be disabled.
+#[tauri::command]
+pub async fn set_extension_enabled(
+    app_handle: AppHandle,
+    name: String,
+    enabled: bool,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<Extension>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let extensions = extensions::set_enabled(&paths, &name, enabled)?;
+    apply_changes(app_handle, apply, &paths, extensions, extensions::list).await
+}
+
+/// Copies a local `.js` file into the Extensions folder. It is enabled
+/// unless `enable` is `false`.
+#[tauri::command]
+pub async fn install_extension(
+    app_handle: AppHandle,
+    path: String,
+    enable: Option<bool>,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<Extension>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let extensions = extensions::install(&paths, &PathBuf::from(path), enable.unwrap_or(true))?;
+    apply_changes(app_handle, apply, &paths, extensions, extensions::list).await
+}
+
+#[tauri::command]
+pub async fn remove_extension(
+    app_handle: AppHandle,
+    name: String,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<Extension>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let extensions = extensions::remove(&paths, &name)?;
+    apply_changes(app_handle, apply, &paths, extensions, extensions::list).await
+}
+
+#[tauri::command]
+pub async fn list_custom_apps() -> InstallerResult<Vec<CustomApp>> {
+    custom_apps::list(&SpicetifyPaths::resolve()?)
+}
+
+/// Toggles the custom app folder `name` in the `custom_apps` list. Apps
+/// whose manifest fails validation cannot be enabled.
+#[tauri::command]
+pub async fn set_custom_app_enabled(
+    app_handle: AppHandle,
+    name: String,
+    enabled: bool,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<CustomApp>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let apps = custom_apps::set_enabled(&paths, &name, enabled)?;
+    apply_changes(app_handle, apply, &paths, apps, custom_apps::list).await
+}
+
+/// Installs a custom app from a local folder or `.zip`. `name` defaults to
+/// the folder or zip name, and the app is enabled unless `enable` is `false`.
+#[tauri::command]
+pub async fn install_custom_app(
+    app_handle: AppHandle,
+    path: String,
+    name: Option<String>,
+    enable: Option<bool>,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<CustomApp>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let apps = custom_apps::install(
+        &paths,
+        &PathBuf::from(path),
+        name.as_deref(),
+        enable.unwrap_or(true),
+    )?;
+    apply_changes(app_handle, apply, &paths, apps, custom_apps::list).await
+}
+
+#[tauri::command]
+pub async fn remove_custom_app(
+    app_handle: AppHandle,
+    name: String,
+    apply: Option<bool>,
+) -> InstallerResult<Vec<CustomApp>> {
+    let paths = SpicetifyPaths::resolve()?;
+    let apps = custom_apps::remove(&paths, &name)?;
+    apply_changes(app_handle, apply, &paths, apps, custom_apps::list).await
+}
+
+#[tauri::command]
+pub async fn list_profiles() -> InstallerResult<Vec<SetupProfile>> {
+    profiles::list()
+}
+
+/// Saves the current theme, extensions, custom apps and settings as `name`.
+#[tauri::command]
+pub async fn save_profile(name: String) -> InstallerResult<Vec<SetupProfile>> {
+    profiles::save(&SpicetifyPaths::resolve()?, &name)
+}
+
+/// Rewrites `config-xpui.ini` from `name` and, unless `apply` is `false`,
+/// runs `spicetify apply`.
+#[tauri::command]
+pub async fn activate_profile(
+    app_handle: AppHandle,
+    name: String,
+    apply: Option<bool>,
+) -> InstallerResult<SetupProfile> {
+    let profile = profiles::activate(&SpicetifyPaths::resolve()?, &name)?;
+
+    if apply.unwrap_or(true) {
+        Operation::Apply.run(app_handle).await?;
+    }
+
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn delete_profile(name: String) -> InstallerResult<Vec<SetupProfile>> {
+    profiles::delete(&name)
+}
+
+/// Whether Marketplace is installed and enabled, and its version. Install
+/// and removal run as the `installMarketplace` and `uninstallMarketplace`
+/// operations.
+#[tauri::command]
+pub async fn get_marketplace_status() -> InstallerResult<MarketplaceStatus> {
+    marketplace::status().await
+}
+
+/// Unless `apply` is `false`, runs the same `spicetify apply` step as a
+/// repair, then lists again so statuses reflect the applied state.
+async fn apply_changes<T>(
+    app_handle: AppHandle,
+    apply: Option<bool>,
+    paths: &SpicetifyPaths,
+    current: T,
+    refresh: fn(&SpicetifyPaths) -> InstallerResult<T>,
+) -> InstallerResult<T> {
+    if !apply.unwrap_or(true) {
+        return Ok(current);
+    }
+    Operation::Apply.run(app_handle).await?;
+    refresh(paths)
+}
+
+#[tauri::command]
+pub async fn download_update(_app_handle: AppHandle) -> InstallerResult<()> {
     let version_info = check_versions().await?;
 
     if !version_info.has_installer_update {
-        return Err("No updates available".to_string());
+        return Err(InstallerError::invalid_data("No updates available"));
     }
 
     let download_url = version_info
         .latest_installer_url
-        .ok_or("No download URL available")?;
+        .ok_or_else(|| InstallerError::invalid_data("No download URL available"))?;
 
     let temp_dir = env::temp_dir();
     let filename = download_url
@@ -963,13 +898,13 @@ pub async fn download_update(_app_handle: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn open_faq_url() -> Result<(), String> {
+pub async fn open_faq_url() -> InstallerResult<()> {
     #[cfg(target_os = "windows")]
     {
         Command::new("cmd")
             .args(&["/c", "start", "https://spicetify.app/docs/faq/"])
             .spawn()
-            .map_err(|e| format!("Failed to open URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open URL", e))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -977,7 +912,7 @@ pub async fn open_faq_url() -> Result<(), String> {
         Command::new("open")
             .arg("https://spicetify.app/docs/faq/")
             .spawn()
-            .map_err(|e| format!("Failed to open URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open URL", e))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -985,22 +920,23 @@ pub async fn open_faq_url() -> Result<(), String> {
         Command::new("xdg-open")
             .arg("https://spicetify.app/docs/faq/")
             .spawn()
-            .map_err(|e| format!("Failed to open URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open URL", e))?;
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn open_download_url() -> Result<(), String> {
-    let download_url = "https://github.com/FIREPAWER07/spicetify-installer/releases/latest";
+pub async fn open_download_url() -> InstallerResult<()> {
+    let download_url = ReleaseSource::current().installer.latest_release_page_url();
+    let download_url = download_url.as_str();
 
     #[cfg(target_os = "windows")]
     {
         Command::new("cmd")
             .args(&["/c", "start", download_url])
             .spawn()
-            .map_err(|e| format!("Failed to open download URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open download URL", e))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -1008,7 +944,7 @@ pub async fn open_download_url() -> Result<(), String> {
         Command::new("open")
             .arg(download_url)
             .spawn()
-            .map_err(|e| format!("Failed to open download URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open download URL", e))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -1016,14 +952,24 @@ pub async fn open_download_url() -> Result<(), String> {
         Command::new("xdg-open")
             .arg(download_url)
             .spawn()
-            .map_err(|e| format!("Failed to open download URL: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to open download URL", e))?;
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn check_spicetify_location() -> Result<String, String> {
+pub async fn list_spicetify_versions() -> InstallerResult<Vec<SpicetifyRelease>> {
+    list_spicetify_releases().await
+}
+
+#[tauri::command]
+pub async fn probe_spicetify() -> InstallerResult<SpicetifyProbe> {
+    Ok(SpicetifyProbe::run())
+}
+
+#[tauri::command]
+pub async fn check_spicetify_location() -> InstallerResult<String> {
     let locations = vec![
         "%LOCALAPPDATA%\\spicetify",
         "%USERPROFILE%\\spicetify-cli",
@@ -1035,8 +981,7 @@ pub async fn check_spicetify_location() -> Result<String, String> {
 
     for location in locations {
         let expanded_location = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .creation_flags(0x08000000)
+            hidden_command("cmd")
                 .args(&["/c", &format!("echo {}", location)])
                 .output()
                 .ok()
@@ -1055,8 +1000,7 @@ pub async fn check_spicetify_location() -> Result<String, String> {
         result.push_str(&format!("Checking: {}\n", expanded_location));
 
         let exists = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .creation_flags(0x08000000)
+            hidden_command("cmd")
                 .args(&["/c", &format!("if exist \"{}\" echo 1", expanded_location)])
                 .output()
                 .ok()
@@ -1081,8 +1025,7 @@ pub async fn check_spicetify_location() -> Result<String, String> {
 
     result.push_str("\nChecking PATH for spicetify:\n");
     let path_check = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .creation_flags(0x08000000)
+        hidden_command("cmd")
             .args(&["/c", "where spicetify 2>NUL"])
             .output()
     } else {
@@ -1109,7 +1052,84 @@ pub async fn check_spicetify_location() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn check_for_app_updates(app_handle: AppHandle) -> Result<UpdateInfo, String> {
+pub async fn get_spicetify_config() -> InstallerResult<SpicetifyConfig> {
+    Ok(ConfigFile::load()?.to_view())
+}
+
+/// Changes one value in `config-xpui.ini`, then runs `spicetify apply` when
+/// `apply` is set so the change reaches Spotify.
+#[tauri::command]
+pub async fn set_spicetify_config_value(
+    app_handle: AppHandle,
+    section: String,
+    key: String,
+    value: String,
+    apply: Option<bool>,
+) -> InstallerResult<SpicetifyConfig> {
+    let mut config = ConfigFile::load()?;
+    config.set(&section, &key, &value)?;
+    config.save()?;
+    eprintln!("Set [{}] {} = {} in {:?}", section, key, value, config.path());
+
+    if apply.unwrap_or(false) {
+        Operation::Apply.run(app_handle).await?;
+    }
+
+    Ok(config.to_view())
+}
+
+/// Writes a diagnostics zip to `destination`, or the Downloads folder, and
+/// returns where it was saved.
+#[tauri::command]
+pub async fn export_diagnostics(
+    app_handle: AppHandle,
+    destination: Option<String>,
+) -> InstallerResult<String> {
+    let path = diagnostics::export(&app_handle, destination.map(PathBuf::from)).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn get_release_source() -> InstallerResult<ReleaseSource> {
+    Ok(ReleaseSource::current())
+}
+
+#[tauri::command]
+pub async fn set_release_source(source: ReleaseSource) -> InstallerResult<ReleaseSource> {
+    source.validate()?;
+    let settings = settings::update(|settings| settings.release_source = source)?;
+    Ok(settings.release_source)
+}
+
+#[tauri::command]
+pub async fn get_release_channel() -> InstallerResult<ReleaseChannel> {
+    Ok(settings::current().release_channel)
+}
+
+#[tauri::command]
+pub async fn set_release_channel(channel: ReleaseChannel) -> InstallerResult<ReleaseChannel> {
+    let settings = settings::update(|settings| settings.release_channel = channel)?;
+    Ok(settings.release_channel)
+}
+
+#[tauri::command]
+pub async fn get_download_cache() -> InstallerResult<CacheSummary> {
+    download_cache::summary()
+}
+
+#[tauri::command]
+pub async fn set_download_cache_limit(limit_mb: u64) -> InstallerResult<CacheSummary> {
+    settings::update(|settings| settings.download_cache_limit_mb = limit_mb)?;
+    download_cache::summary()
+}
+
+#[tauri::command]
+pub async fn clear_download_cache() -> InstallerResult<CacheSummary> {
+    download_cache::clear()
+}
+
+#[tauri::command]
+pub async fn check_for_app_updates(app_handle: AppHandle) -> InstallerResult<UpdateInfo> {
     let update_manager = UpdateManager::new(app_handle);
     update_manager.check_for_updates().await
 }
@@ -1118,19 +1138,31 @@ pub async fn check_for_app_updates(app_handle: AppHandle) -> Result<UpdateInfo,
 pub async fn download_and_install_update(
     app_handle: AppHandle,
     download_url: String,
-) -> Result<(), String> {
+) -> InstallerResult<()> {
     let update_manager = UpdateManager::new(app_handle);
     update_manager.download_and_install_update(download_url).await
 }
 
 #[tauri::command]
-pub async fn restart_application(app_handle: AppHandle) -> Result<(), String> {
+pub async fn list_installer_versions(app_handle: AppHandle) -> InstallerResult<Vec<InstallerVersion>> {
+    let update_manager = UpdateManager::new(app_handle);
+    update_manager.list_installer_versions()
+}
+
+#[tauri::command]
+pub async fn rollback_installer(app_handle: AppHandle, version: String) -> InstallerResult<()> {
+    let update_manager = UpdateManager::new(app_handle);
+    update_manager.rollback_installer(&version).await
+}
+
+#[tauri::command]
+pub async fn restart_application(app_handle: AppHandle) -> InstallerResult<()> {
     let current_exe = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+        .map_err(|e| InstallerError::io("Failed to get executable path", e))?;
 
     std::process::Command::new(&current_exe)
         .spawn()
-        .map_err(|e| format!("Failed to restart application: {}", e))?;
+        .map_err(|e| InstallerError::io("Failed to restart application", e))?;
 
     app_handle.exit(0);
     Ok(())
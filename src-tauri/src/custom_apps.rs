@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::assets::{AssetKind, AssetStatus};
+use crate::error::{InstallerError, InstallerResult};
+use crate::spicetify_config::SpicetifyPaths;
+use crate::spicetify_install::copy_dir_contents;
+
+const CUSTOM_APPS: AssetKind = AssetKind {
+    folder: "CustomApps",
+    key: "custom_apps",
+    label: "Custom app",
+    is_asset: Path::is_dir,
+};
+const MANIFEST_FILE: &str = "manifest.json";
+/// Spicetify loads this file from every custom app.
+const ENTRY_FILE: &str = "index.js";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomApp {
+    /// Folder name, as written in the config.
+    pub name: String,
+    /// `name` from `manifest.json`, shown in Spotify's sidebar.
+    pub display_name: Option<String>,
+    pub path: Option<String>,
+    /// Shipped with the CLI rather than the user's `CustomApps` folder.
+    pub bundled: bool,
+    pub status: AssetStatus,
+    /// Why the app's manifest or files are invalid.
+    pub problem: Option<String>,
+}
+
+/// The parts of `manifest.json` Spicetify reads.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    /// Either a string or names keyed by locale.
+    name: Option<serde_json::Value>,
+    #[serde(default)]
+    subfiles: Vec<String>,
+    #[serde(default)]
+    subfiles_extension: Vec<String>,
+}
+
+/// Apps from the user's `CustomApps` folder, then ones bundled with the
+/// CLI, followed by enabled apps that have no folder.
+pub(crate) fn list(paths: &SpicetifyPaths) -> InstallerResult<Vec<CustomApp>> {
+    Ok(CUSTOM_APPS
+        .list(paths)?
+        .into_iter()
+        .map(|asset| {
+            let (display_name, problem) = match asset.path.as_deref().map(validate) {
+                Some(Ok(display_name)) => (Some(display_name), None),
+                Some(Err(e)) => (None, Some(e.to_string())),
+                None => (None, None),
+            };
+            CustomApp {
+                name: asset.name,
+                display_name,
+                path: asset.path.map(|path| path.to_string_lossy().to_string()),
+                bundled: asset.bundled,
+                status: asset.status,
+                problem,
+            }
+        })
+        .collect())
+}
+
+/// Adds `name` to or drops it from the `custom_apps` list. Only installed
+/// apps with a valid manifest can be enabled.
+pub(crate) fn set_enabled(
+    paths: &SpicetifyPaths,
+    name: &str,
+    enabled: bool,
+) -> InstallerResult<Vec<CustomApp>> {
+    if enabled {
+        let app = list(paths)?
+            .into_iter()
+            .find(|app| app.name == name && app.path.is_some())
+            .ok_or_else(|| {
+                InstallerError::invalid_data(format!("Custom app {} is not installed", name))
+                    .with_remediation("Install the app first.")
+            })?;
+        if let Some(problem) = app.problem {
+            return Err(InstallerError::invalid_data(format!(
+                "Custom app {} is invalid: {}",
+                name, problem
+            )));
+        }
+    }
+
+    CUSTOM_APPS.set_listed(paths, name, enabled)?;
+    list(paths)
+}
+
+/// Installs the app in the folder or `.zip` at `source` into the user's
+/// `CustomApps` folder, replacing any app with the same name. `name`
+/// defaults to the folder or zip name.
+pub(crate) fn install(
+    paths: &SpicetifyPaths,
+    source: &Path,
+    name: Option<&str>,
+    enable: bool,
+) -> InstallerResult<Vec<CustomApp>> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => default_name(source)?,
+    };
+    validate_name(&name)?;
+
+    if source.is_dir() {
+        copy_app(paths, source, &name)?;
+    } else if source.is_file() {
+        let staging_dir = env::temp_dir().join("spicetify_custom_app");
+        let _ = fs::remove_dir_all(&staging_dir);
+        let result = archive::extract(source, &staging_dir)
+            .and_then(|_| find_app_root(&staging_dir))
+            .and_then(|root| copy_app(paths, &root, &name));
+        let _ = fs::remove_dir_all(&staging_dir);
+        result?;
+    } else {
+        return Err(
+            InstallerError::invalid_data(format!("{} does not exist", source.display()))
+                .with_remediation("Pick the app's folder or .zip file."),
+        );
+    }
+
+    if enable {
+        CUSTOM_APPS.set_listed(paths, &name, true)?;
+    }
+    list(paths)
+}
+
+/// Deletes a user app and drops it from the `custom_apps` list.
+pub(crate) fn remove(paths: &SpicetifyPaths, name: &str) -> InstallerResult<Vec<CustomApp>> {
+    CUSTOM_APPS.remove(paths, name)?;
+    list(paths)
+}
+
+/// Checks `manifest.json` and the files it lists, returning the app's
+/// display name.
+fn validate(app_dir: &Path) -> InstallerResult<String> {
+    let contents = fs::read_to_string(app_dir.join(MANIFEST_FILE))
+        .map_err(|e| InstallerError::io(format!("Failed to read {}", MANIFEST_FILE), e))?;
+    let manifest: Manifest = serde_json::from_str(&contents).map_err(|e| {
+        InstallerError::invalid_data(format!("{} is not valid JSON", MANIFEST_FILE)).with_detail(e)
+    })?;
+
+    let display_name = match &manifest.name {
+        Some(serde_json::Value::String(name)) => Some(name.trim().to_string()),
+        Some(serde_json::Value::Object(names)) => names
+            .get("en")
+            .or_else(|| names.values().next())
+            .and_then(|name| name.as_str())
+            .map(|name| name.trim().to_string()),
+        _ => None,
+    }
+    .filter(|name| !name.is_empty())
+    .ok_or_else(|| InstallerError::invalid_data(format!("{} has no name", MANIFEST_FILE)))?;
+
+    let required = std::iter::once(ENTRY_FILE)
+        .chain(manifest.subfiles.iter().map(String::as_str))
+        .chain(manifest.subfiles_extension.iter().map(String::as_str));
+    for file in required {
+        if !app_dir.join(file).is_file() {
+            return Err(InstallerError::invalid_data(format!(
+                "Missing file {}",
+                file
+            )));
+        }
+    }
+
+    Ok(display_name)
+}
+
+/// The folder holding `manifest.json`: the archive root, or its only
+/// top-level folder as most release zips have.
+fn find_app_root(extracted: &Path) -> InstallerResult<PathBuf> {
+    if extracted.join(MANIFEST_FILE).is_file() {
+        return Ok(extracted.to_path_buf());
+    }
+
+    let folders: Vec<PathBuf> = fs::read_dir(extracted)
+        .map_err(|e| InstallerError::io("Failed to read extracted app", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    match folders.as_slice() {
+        [folder] if folder.join(MANIFEST_FILE).is_file() => Ok(folder.clone()),
+        _ => Err(
+            InstallerError::invalid_data(format!("The archive has no {}", MANIFEST_FILE))
+                .with_remediation("Use the app's release zip, which contains its manifest.json."),
+        ),
+    }
+}
+
+fn copy_app(paths: &SpicetifyPaths, app_dir: &Path, name: &str) -> InstallerResult<()> {
+    let display_name = validate(app_dir)?;
+
+    let target = CUSTOM_APPS.user_dir(paths)?.join(name);
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .map_err(|e| InstallerError::io("Failed to replace custom app", e))?;
+    }
+    copy_dir_contents(app_dir, &target, None)?;
+    eprintln!(
+        "Installed custom app {} ({}) into {:?}",
+        name, display_name, target
+    );
+    Ok(())
+}
+
+fn default_name(source: &Path) -> InstallerResult<String> {
+    let name = if source.is_dir() {
+        source.file_name()
+    } else {
+        source.file_stem()
+    };
+    name.map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| InstallerError::invalid_data("Custom app source has no name"))
+}
+
+/// App names are folder names and items in the `|`-separated config list.
+fn validate_name(name: &str) -> InstallerResult<()> {
+    if name.trim().is_empty()
+        || name.starts_with('.')
+        || name.contains(['|', '/', '\\', ':', '*', '?', '"', '<', '>'])
+    {
+        return Err(InstallerError::invalid_data(format!(
+            "Invalid custom app name: {:?}",
+            name
+        )));
+    }
+    Ok(())
+}
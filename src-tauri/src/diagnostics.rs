@@ -0,0 +1,229 @@
+use serde_json::json;
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::commands::check_spicetify_location;
+use crate::error::{InstallerError, InstallerResult};
+use crate::operation_history;
+use crate::release_source::ReleaseSource;
+use crate::settings;
+use crate::spicetify_config::SpicetifyPaths;
+use crate::spicetify_probe::SpicetifyProbe;
+use crate::spotify;
+
+/// Operations included in the bundle, newest first.
+const RECENT_OPERATIONS: usize = 20;
+const RECENT_UPDATER_LOGS: usize = 5;
+/// Config keys whose values are dropped regardless of content.
+const SECRET_KEYS: [&str; 4] = ["token", "secret", "password", "api_key"];
+
+/// Writes a zip with everything needed to debug an installation and
+/// returns its path. User names and home folders are replaced in every file.
+pub(crate) async fn export(
+    app_handle: &AppHandle,
+    destination: Option<PathBuf>,
+) -> InstallerResult<PathBuf> {
+    let redactor = Redactor::for_current_user();
+    let mut files: Vec<(String, String)> = Vec::new();
+
+    let probe = SpicetifyProbe::run();
+    files.push(("probe.json".to_string(), to_json(&probe)));
+
+    let location = check_spicetify_location()
+        .await
+        .unwrap_or_else(|e| format!("Location check failed: {}\n", e));
+    files.push(("location.txt".to_string(), location));
+
+    let operations = match operation_history::recent(RECENT_OPERATIONS) {
+        Ok(records) => records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap_or_default() + "\n")
+            .collect(),
+        Err(e) => format!("Operation history unavailable: {}\n", e),
+    };
+    files.push(("operations.jsonl".to_string(), operations));
+
+    for log in updater_logs() {
+        if let (Some(name), Ok(contents)) = (log.file_name(), fs::read_to_string(&log)) {
+            files.push((format!("updater-logs/{}", name.to_string_lossy()), contents));
+        }
+    }
+
+    match SpicetifyPaths::from_probe(&probe).and_then(|paths| {
+        fs::read_to_string(&paths.config)
+            .map_err(|e| InstallerError::io("Failed to read config", e))
+    }) {
+        Ok(config) => files.push(("config-xpui.ini".to_string(), redact_config(&config))),
+        Err(e) => files.push((
+            "config-xpui.txt".to_string(),
+            format!("Config unavailable: {}\n", e),
+        )),
+    }
+
+    files.push((
+        "environment.json".to_string(),
+        to_json(&environment(app_handle, &probe)),
+    ));
+
+    let destination = match destination {
+        Some(destination) => destination,
+        None => default_destination(app_handle),
+    };
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| InstallerError::io("Failed to create diagnostics folder", e))?;
+    }
+
+    let file = File::create(&destination)
+        .map_err(|e| InstallerError::io("Failed to create diagnostics bundle", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, contents) in files {
+        zip.start_file(name, options).map_err(|e| {
+            InstallerError::invalid_data("Failed to write diagnostics bundle").with_detail(e)
+        })?;
+        zip.write_all(redactor.apply(&contents).as_bytes())
+            .map_err(|e| InstallerError::io("Failed to write diagnostics bundle", e))?;
+    }
+    zip.finish().map_err(|e| {
+        InstallerError::invalid_data("Failed to write diagnostics bundle").with_detail(e)
+    })?;
+
+    eprintln!("Diagnostics written to {:?}", destination);
+    Ok(destination)
+}
+
+fn default_destination(app_handle: &AppHandle) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let dir = app_handle
+        .path()
+        .download_dir()
+        .unwrap_or_else(|_| env::temp_dir());
+    dir.join(format!("spicetify-diagnostics-{}.zip", timestamp))
+}
+
+fn environment(app_handle: &AppHandle, probe: &SpicetifyProbe) -> serde_json::Value {
+    let path_entries: Vec<String> = env::var_os("PATH")
+        .map(|path| {
+            env::split_paths(&path)
+                .map(|dir| dir.to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let settings = settings::current();
+
+    json!({
+        "installerVersion": env!("CARGO_PKG_VERSION"),
+        "os": env::consts::OS,
+        "family": env::consts::FAMILY,
+        "arch": env::consts::ARCH,
+        "spicetifyVersion": probe.version,
+        "spotifyVersion": spotify::installed_version(),
+        "releaseSource": ReleaseSource::current(),
+        "releaseChannel": settings.release_channel,
+        "downloadCacheLimitMb": settings.download_cache_limit_mb,
+        "appDataDir": app_handle.path().app_data_dir().ok(),
+        "appConfigDir": app_handle.path().app_config_dir().ok(),
+        "overrides": {
+            "SPICETIFY_INSTALLER_API_BASE_URL": env::var("SPICETIFY_INSTALLER_API_BASE_URL").ok(),
+            "SPICETIFY_INSTALLER_DOWNLOAD_BASE_URL": env::var("SPICETIFY_INSTALLER_DOWNLOAD_BASE_URL").ok(),
+            "SPICETIFY_CONFIG": env::var("SPICETIFY_CONFIG").ok(),
+        },
+        "path": path_entries,
+    })
+}
+
+/// The newest `spicetify_updater_*.log` files the updater script left in
+/// the temp folder.
+fn updater_logs() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(env::temp_dir()) else {
+        return Vec::new();
+    };
+
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| {
+                    name.starts_with("spicetify_updater_") && name.ends_with(".log")
+                })
+        })
+        .collect();
+
+    // Names end in a sortable timestamp
+    logs.sort();
+    logs.into_iter().rev().take(RECENT_UPDATER_LOGS).collect()
+}
+
+/// Blanks values of keys that look like credentials.
+fn redact_config(config: &str) -> String {
+    config
+        .lines()
+        .map(|line| match line.split_once('=') {
+            Some((key, _))
+                if SECRET_KEYS
+                    .iter()
+                    .any(|secret| key.trim().to_lowercase().contains(secret)) =>
+            {
+                format!("{}= <redacted>", key)
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replaces the home folder and user name, which appear in almost every
+/// path, with placeholders.
+struct Redactor {
+    replacements: Vec<(String, &'static str)>,
+}
+
+impl Redactor {
+    fn for_current_user() -> Self {
+        let mut replacements = Vec::new();
+        for var in ["USERPROFILE", "HOME"] {
+            if let Ok(home) = env::var(var) {
+                if !home.is_empty() {
+                    replacements.push((home.replace('\\', "\\\\"), "~"));
+                    replacements.push((home, "~"));
+                }
+            }
+        }
+        for var in ["USERNAME", "USER"] {
+            if let Ok(user) = env::var(var) {
+                // Very short names would mangle unrelated text
+                if user.len() >= 3 {
+                    replacements.push((user, "<user>"));
+                }
+            }
+        }
+        // Longest first, so the home folder wins over the user name in it
+        replacements.sort_by_key(|(text, _)| std::cmp::Reverse(text.len()));
+        Self { replacements }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (secret, placeholder)| {
+                text.replace(secret.as_str(), placeholder)
+            })
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::checksum::sha256_file;
+use crate::error::{InstallerError, InstallerResult};
+use crate::settings;
+
+const CACHE_DIR: &str = "downloads";
+const INDEX_FILE: &str = "index.json";
+const BLOBS_DIR: &str = "blobs";
+
+/// Product a cached download belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CachedProduct {
+    /// A Spicetify CLI release archive.
+    Spicetify,
+    /// A build of this installer.
+    Installer,
+    /// A Spicetify Marketplace release archive.
+    Marketplace,
+}
+
+/// Identifies a download by what it is rather than where it came from, so a
+/// mirror and GitHub share the same entry.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheKey {
+    pub product: CachedProduct,
+    pub version: String,
+    pub file_name: String,
+}
+
+impl CacheKey {
+    pub(crate) fn new(product: CachedProduct, version: &str, file_name: &str) -> Self {
+        Self {
+            product,
+            version: version.trim_start_matches('v').to_string(),
+            file_name: file_name.to_string(),
+        }
+    }
+}
+
+/// One cached file. The file itself is stored once under its SHA-256, so
+/// entries with identical content share it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub product: CachedProduct,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub file_name: String,
+    pub sha256: String,
+    pub size: u64,
+    /// Seconds since the Unix epoch; the least recently used entries are
+    /// evicted first.
+    pub last_used: u64,
+}
+
+impl CacheEntry {
+    fn matches(&self, key: &CacheKey) -> bool {
+        self.product == key.product
+            && self.version == key.version
+            && self.file_name == key.file_name
+            && self.os == env::consts::OS
+            && self.arch == env::consts::ARCH
+    }
+}
+
+/// Contents and size of the download cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheSummary {
+    pub path: String,
+    pub entries: Vec<CacheEntry>,
+    pub total_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+static CACHE_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Resolves the cache folder inside the app data directory.
+pub(crate) fn init(app_handle: &AppHandle) -> InstallerResult<()> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| {
+        InstallerError::invalid_data("Failed to resolve the app data directory").with_detail(e)
+    })?;
+    *CACHE_ROOT.lock().unwrap() = Some(data_dir.join(CACHE_DIR));
+    Ok(())
+}
+
+/// Path of a cached, intact copy of `key` for this OS and architecture.
+/// A file whose content no longer matches its hash is dropped.
+pub(crate) fn lookup(key: &CacheKey) -> Option<PathBuf> {
+    let guard = CACHE_ROOT.lock().unwrap();
+    let root = guard.as_ref()?;
+    let mut entries = read_index(root);
+
+    let index = entries.iter().position(|entry| entry.matches(key))?;
+    let blob = blob_path(root, &entries[index].sha256, &entries[index].file_name);
+
+    let intact = sha256_file(&blob)
+        .map(|actual| actual == entries[index].sha256)
+        .unwrap_or(false);
+    if !intact {
+        eprintln!("Dropping corrupt cache entry {}", entries[index].file_name);
+        entries.remove(index);
+        remove_unreferenced_blobs(root, &entries);
+        let _ = write_index(root, &entries);
+        return None;
+    }
+
+    entries[index].last_used = now();
+    let _ = write_index(root, &entries);
+    Some(blob)
+}
+
+/// Moves a finished download into the cache and returns its new location.
+/// Least recently used entries are evicted to stay within the size limit.
+pub(crate) fn store(key: &CacheKey, file: &Path) -> InstallerResult<PathBuf> {
+    insert(key, file, true)
+}
+
+/// Like [`store`], but leaves `file` in place.
+pub(crate) fn store_copy(key: &CacheKey, file: &Path) -> InstallerResult<PathBuf> {
+    insert(key, file, false)
+}
+
+fn insert(key: &CacheKey, file: &Path, move_file: bool) -> InstallerResult<PathBuf> {
+    let guard = CACHE_ROOT.lock().unwrap();
+    let root = guard
+        .as_ref()
+        .ok_or_else(|| InstallerError::invalid_data("The download cache has not been set up"))?;
+
+    let sha256 = sha256_file(file)?;
+    let size = fs::metadata(file)
+        .map_err(|e| InstallerError::io("Failed to read download", e))?
+        .len();
+
+    let blob = blob_path(root, &sha256, &key.file_name);
+    if let Some(parent) = blob.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| InstallerError::io("Failed to create download cache directory", e))?;
+    }
+    if !blob.exists() {
+        // The temp folder can be on another drive, so a failed rename falls
+        // back to copying.
+        let renamed = move_file && fs::rename(file, &blob).is_ok();
+        if !renamed {
+            fs::copy(file, &blob).map_err(|e| InstallerError::io("Failed to cache download", e))?;
+        }
+    }
+    if move_file {
+        let _ = fs::remove_file(file);
+    }
+
+    let mut entries = read_index(root);
+    entries.retain(|entry| !entry.matches(key));
+    entries.push(CacheEntry {
+        product: key.product,
+        version: key.version.clone(),
+        os: env::consts::OS.to_string(),
+        arch: env::consts::ARCH.to_string(),
+        file_name: key.file_name.clone(),
+        sha256,
+        size,
+        last_used: now(),
+    });
+
+    evict(&mut entries, limit_bytes(), key);
+    remove_unreferenced_blobs(root, &entries);
+    write_index(root, &entries)?;
+
+    Ok(blob)
+}
+
+/// Cached entries for this OS and architecture, most recently used first.
+pub(crate) fn entries(product: CachedProduct) -> Vec<CacheEntry> {
+    let guard = CACHE_ROOT.lock().unwrap();
+    let Some(root) = guard.as_ref() else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<CacheEntry> = read_index(root)
+        .into_iter()
+        .filter(|entry| {
+            entry.product == product
+                && entry.os == env::consts::OS
+                && entry.arch == env::consts::ARCH
+        })
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.last_used));
+    entries
+}
+
+pub(crate) fn summary() -> InstallerResult<CacheSummary> {
+    let guard = CACHE_ROOT.lock().unwrap();
+    let root = guard
+        .as_ref()
+        .ok_or_else(|| InstallerError::invalid_data("The download cache has not been set up"))?;
+
+    let entries = read_index(root);
+    Ok(CacheSummary {
+        path: root.to_string_lossy().to_string(),
+        total_bytes: stored_bytes(&entries),
+        entries,
+        limit_bytes: limit_bytes(),
+    })
+}
+
+/// Deletes every cached download.
+pub(crate) fn clear() -> InstallerResult<CacheSummary> {
+    {
+        let guard = CACHE_ROOT.lock().unwrap();
+        let root = guard.as_ref().ok_or_else(|| {
+            InstallerError::invalid_data("The download cache has not been set up")
+        })?;
+
+        if root.exists() {
+            fs::remove_dir_all(root)
+                .map_err(|e| InstallerError::io("Failed to clear the download cache", e))?;
+        }
+        eprintln!("Cleared download cache at {:?}", root);
+    }
+    summary()
+}
+
+fn limit_bytes() -> u64 {
+    settings::current().download_cache_limit_mb * 1024 * 1024
+}
+
+/// Blobs keep the archive suffix of the download, since extraction picks
+/// the format from it.
+fn blob_path(root: &Path, sha256: &str, file_name: &str) -> PathBuf {
+    let lowered = file_name.to_lowercase();
+    let suffix = if lowered.ends_with(".tar.gz") {
+        ".tar.gz".to_string()
+    } else {
+        Path::new(&lowered)
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default()
+    };
+    root.join(BLOBS_DIR).join(format!("{}{}", sha256, suffix))
+}
+
+fn read_index(root: &Path) -> Vec<CacheEntry> {
+    fs::read_to_string(root.join(INDEX_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(root: &Path, entries: &[CacheEntry]) -> InstallerResult<()> {
+    fs::create_dir_all(root)
+        .map_err(|e| InstallerError::io("Failed to create download cache directory", e))?;
+    let contents = serde_json::to_string_pretty(entries).map_err(|e| {
+        InstallerError::invalid_data("Failed to serialize the download cache index").with_detail(e)
+    })?;
+    fs::write(root.join(INDEX_FILE), contents)
+        .map_err(|e| InstallerError::io("Failed to write the download cache index", e))
+}
+
+/// Bytes on disk; entries sharing a blob count once.
+fn stored_bytes(entries: &[CacheEntry]) -> u64 {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut total = 0;
+    for entry in entries {
+        if !seen.contains(&entry.sha256.as_str()) {
+            seen.push(&entry.sha256);
+            total += entry.size;
+        }
+    }
+    total
+}
+
+/// Drops the least recently used entries until the cache fits in `limit`.
+/// The entry for `keep`, just inserted, is never evicted, even when it alone
+/// exceeds the limit or shares its `last_used` second with another entry.
+fn evict(entries: &mut Vec<CacheEntry>, limit: u64, keep: &CacheKey) {
+    entries.sort_by_key(|entry| Reverse(entry.last_used));
+    while stored_bytes(entries) > limit {
+        let Some(index) = entries.iter().rposition(|entry| !entry.matches(keep)) else {
+            break;
+        };
+        let evicted = entries.remove(index);
+        eprintln!(
+            "Evicting {} v{} from the download cache",
+            evicted.file_name, evicted.version
+        );
+    }
+}
+
+fn remove_unreferenced_blobs(root: &Path, entries: &[CacheEntry]) {
+    let Ok(blobs) = fs::read_dir(root.join(BLOBS_DIR)) else {
+        return;
+    };
+    for blob in blobs.flatten() {
+        let name = blob.file_name().to_string_lossy().to_string();
+        let sha256 = name.split('.').next().unwrap_or_default();
+        if !entries.iter().any(|entry| entry.sha256 == sha256) {
+            let _ = fs::remove_file(blob.path());
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str, size: u64, last_used: u64) -> CacheEntry {
+        CacheEntry {
+            product: CachedProduct::Spicetify,
+            version: version.to_string(),
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            file_name: format!("spicetify-{}.zip", version),
+            sha256: version.to_string(),
+            size,
+            last_used,
+        }
+    }
+
+    fn key(version: &str) -> CacheKey {
+        CacheKey::new(
+            CachedProduct::Spicetify,
+            version,
+            &format!("spicetify-{}.zip", version),
+        )
+    }
+
+    #[test]
+    fn eviction() {
+        // (entries as (version, size, last_used), limit, inserted, kept versions)
+        type Case<'a> = (&'a [(&'a str, u64, u64)], u64, &'a str, &'a [&'a str]);
+        let cases: [Case; 4] = [
+            (&[("1", 10, 1), ("2", 10, 2), ("3", 10, 3)], 25, "3", &["3", "2"]),
+            (&[("1", 10, 5), ("2", 10, 5)], 15, "2", &["2"]),
+            (&[("2", 10, 5), ("1", 10, 5)], 15, "2", &["2"]),
+            (&[("1", 10, 1), ("2", 50, 2)], 20, "2", &["2"]),
+        ];
+
+        for (existing, limit, inserted, kept) in cases {
+            let mut entries: Vec<CacheEntry> = existing
+                .iter()
+                .map(|(version, size, last_used)| entry(version, *size, *last_used))
+                .collect();
+            evict(&mut entries, limit, &key(inserted));
+            let versions: Vec<&str> = entries.iter().map(|entry| entry.version.as_str()).collect();
+            assert_eq!(versions, kept, "limit {} after inserting {}", limit, inserted);
+        }
+    }
+}
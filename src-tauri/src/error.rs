@@ -0,0 +1,163 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Broad category of a failure, so the frontend can react to it (retry,
+/// offer an install, wait for a rate limit) instead of showing raw text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    /// The request never got a response: offline, DNS, TLS, timeout.
+    Network,
+    /// GitHub refused the request because the API rate limit was hit.
+    RateLimited,
+    /// The server answered with an unexpected status code.
+    HttpStatus,
+    /// A response or file did not have the expected shape.
+    InvalidData,
+    /// The `spicetify` command could not be found.
+    SpicetifyNotFound,
+    /// A script or external process exited unsuccessfully.
+    ProcessFailed,
+    /// A download did not match its published SHA-256 checksum.
+    ChecksumMismatch,
+    /// Reading or writing a local file failed.
+    Io,
+    /// The user cancelled the operation.
+    Cancelled,
+    /// Another operation or check is already running.
+    Busy,
+    /// The request is not supported on this system or input.
+    Unsupported,
+}
+
+/// Error returned by every Tauri command.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallerError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Underlying error text, e.g. the stderr of a failed script.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// What the user can do about it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+pub type InstallerResult<T> = Result<T, InstallerError>;
+
+impl InstallerError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            detail: None,
+            remediation: None,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl fmt::Display) -> Self {
+        let detail = detail.to_string();
+        if !detail.trim().is_empty() {
+            self.detail = Some(detail.trim().to_string());
+        }
+        self
+    }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+
+    pub fn io(message: impl Into<String>, error: std::io::Error) -> Self {
+        Self::new(ErrorKind::Io, message).with_detail(error)
+    }
+
+    pub fn invalid_data(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidData, message)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new(ErrorKind::Cancelled, "Operation cancelled")
+    }
+
+    pub fn busy(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Busy, message)
+            .with_remediation("Wait for the current task to finish and try again.")
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unsupported, message)
+    }
+
+    /// Maps a transport-level `reqwest` failure.
+    pub fn network(message: impl Into<String>, error: reqwest::Error) -> Self {
+        if error.is_decode() {
+            return Self::invalid_data(message).with_detail(error);
+        }
+
+        Self::new(ErrorKind::Network, message)
+            .with_detail(error)
+            .with_remediation("Check your internet connection and try again.")
+    }
+
+    /// Maps a non-success HTTP response, recognising GitHub rate limiting.
+    pub fn http_status(message: impl Into<String>, response: &reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response.headers();
+        let remaining = headers
+            .get("x-ratelimit-remaining")
+            .and_then(|value| value.to_str().ok());
+
+        let rate_limited = status.as_u16() == 429 || (status.as_u16() == 403 && remaining == Some("0"));
+        if rate_limited {
+            let reset = headers
+                .get("x-ratelimit-reset")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok());
+
+            let remediation = match reset {
+                Some(reset) => format!(
+                    "GitHub limits unauthenticated requests. Try again after {} (Unix time).",
+                    reset
+                ),
+                None => "GitHub limits unauthenticated requests. Try again in an hour.".to_string(),
+            };
+
+            return Self::new(ErrorKind::RateLimited, message)
+                .with_detail(format!("Status {}", status))
+                .with_remediation(remediation);
+        }
+
+        Self::new(ErrorKind::HttpStatus, message).with_detail(format!("Status {}", status))
+    }
+
+    /// Maps a failed script run, recognising a missing `spicetify` command.
+    pub fn process_failed(message: impl Into<String>, output: &str) -> Self {
+        let lowered = output.to_lowercase();
+        let not_found = lowered.contains("commandnotfoundexception")
+            || lowered.contains("'spicetify' is not recognized")
+            || lowered.contains("the term 'spicetify' is not recognized")
+            || lowered.contains("spicetify: command not found");
+
+        if not_found {
+            return Self::new(ErrorKind::SpicetifyNotFound, message)
+                .with_detail(output)
+                .with_remediation(
+                    "Install Spicetify first, or restart the installer so the updated PATH is picked up.",
+                );
+        }
+
+        Self::new(ErrorKind::ProcessFailed, message).with_detail(output)
+    }
+}
+
+impl fmt::Display for InstallerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.message, detail),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for InstallerError {}
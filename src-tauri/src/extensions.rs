@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::assets::{AssetKind, AssetStatus};
+use crate::error::{InstallerError, InstallerResult};
+use crate::spicetify_config::SpicetifyPaths;
+
+const EXTENSIONS: AssetKind = AssetKind {
+    folder: "Extensions",
+    key: "extensions",
+    label: "Extension",
+    is_asset: is_script,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extension {
+    /// File name, e.g. `fullAppDisplay.js`, as written in the config.
+    pub name: String,
+    pub path: Option<String>,
+    /// Shipped with the CLI rather than the user's `Extensions` folder.
+    pub bundled: bool,
+    pub status: AssetStatus,
+}
+
+/// Extensions from the user's `Extensions` folder, then ones bundled with
+/// the CLI, followed by enabled extensions that have no file.
+pub(crate) fn list(paths: &SpicetifyPaths) -> InstallerResult<Vec<Extension>> {
+    Ok(EXTENSIONS
+        .list(paths)?
+        .into_iter()
+        .map(|asset| Extension {
+            name: asset.name,
+            path: asset.path.map(|path| path.to_string_lossy().to_string()),
+            bundled: asset.bundled,
+            status: asset.status,
+        })
+        .collect())
+}
+
+/// Adds `name` to or drops it from the `extensions` list. Missing
+/// extensions can be disabled but not enabled.
+pub(crate) fn set_enabled(
+    paths: &SpicetifyPaths,
+    name: &str,
+    enabled: bool,
+) -> InstallerResult<Vec<Extension>> {
+    if enabled {
+        let installed = list(paths)?
+            .into_iter()
+            .any(|extension| extension.name == name && extension.path.is_some());
+        if !installed {
+            return Err(InstallerError::invalid_data(format!(
+                "Extension {} is not installed",
+                name
+            ))
+            .with_remediation("Install the extension file first."));
+        }
+    }
+
+    EXTENSIONS.set_listed(paths, name, enabled)?;
+    list(paths)
+}
+
+/// Copies the `.js` file at `source` into the user's `Extensions` folder,
+/// replacing any extension with the same name.
+pub(crate) fn install(
+    paths: &SpicetifyPaths,
+    source: &Path,
+    enable: bool,
+) -> InstallerResult<Vec<Extension>> {
+    if !is_script(source) {
+        return Err(InstallerError::invalid_data(format!(
+            "{} is not a .js file",
+            source.display()
+        ))
+        .with_remediation("Pick the extension's .js file."));
+    }
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| InstallerError::invalid_data("Extension file has no name"))?;
+    // The config list is separated by `|`
+    if name.contains('|') {
+        return Err(InstallerError::invalid_data(format!(
+            "Extension names cannot contain '|': {}",
+            name
+        )));
+    }
+
+    let dir = EXTENSIONS.user_dir(paths)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| InstallerError::io("Failed to create Extensions folder", e))?;
+    fs::copy(source, dir.join(&name))
+        .map_err(|e| InstallerError::io("Failed to copy extension", e))?;
+    eprintln!("Installed extension {} into {:?}", name, dir);
+
+    if enable {
+        EXTENSIONS.set_listed(paths, &name, true)?;
+    }
+    list(paths)
+}
+
+/// Deletes a user extension and drops it from the `extensions` list.
+pub(crate) fn remove(paths: &SpicetifyPaths, name: &str) -> InstallerResult<Vec<Extension>> {
+    EXTENSIONS.remove(paths, name)?;
+    list(paths)
+}
+
+fn is_script(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("js"))
+}
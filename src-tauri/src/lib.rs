@@ -1,20 +1,114 @@
+mod archive;
+mod assets;
+mod checksum;
+#[cfg(desktop)]
+mod cli;
 mod commands;
+mod custom_apps;
+mod diagnostics;
+mod download_cache;
+mod error;
+mod extensions;
+mod marketplace;
+mod operation_history;
+mod operations;
+mod process_runner;
+mod profiles;
+mod release_source;
+mod settings;
+mod spicetify_config;
+mod spicetify_install;
+mod spicetify_probe;
+mod spotify;
+mod themes;
 mod update_manager;
+mod version;
 
 pub use commands::*;
+pub use error::*;
+pub use operations::*;
+pub use spicetify_probe::*;
 pub use update_manager::*;
 
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_cli::init());
+
+    builder
+        .setup(|app| {
+            if let Err(e) = settings::init(app.handle()) {
+                eprintln!("Failed to load settings, using defaults: {}", e);
+            }
+            if let Err(e) = download_cache::init(app.handle()) {
+                eprintln!("Download cache unavailable: {}", e);
+            }
+            if let Err(e) = operation_history::init(app.handle()) {
+                eprintln!("Operation history unavailable: {}", e);
+            }
+            if let Err(e) = profiles::init(app.handle()) {
+                eprintln!("Profiles unavailable: {}", e);
+            }
+
+            // A subcommand means headless mode: never open the window.
+            #[cfg(desktop)]
+            if cli::start(app) {
+                return Ok(());
+            }
+
+            let window_config = app
+                .config()
+                .app
+                .windows
+                .first()
+                .ok_or("Missing main window configuration")?
+                .clone();
+            tauri::WebviewWindowBuilder::from_config(app.handle(), &window_config)?.build()?;
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
-            commands::execute_powershell_command,
+            commands::execute_operation,
+            commands::cancel_operation,
+            commands::get_operation_history,
+            commands::get_operation_transcript,
             commands::check_versions,
+            commands::list_themes,
+            commands::set_theme,
+            commands::list_extensions,
+            commands::set_extension_enabled,
+            commands::install_extension,
+            commands::remove_extension,
+            commands::list_custom_apps,
+            commands::set_custom_app_enabled,
+            commands::install_custom_app,
+            commands::remove_custom_app,
+            commands::get_marketplace_status,
+            commands::list_profiles,
+            commands::save_profile,
+            commands::activate_profile,
+            commands::delete_profile,
             commands::open_faq_url,
             commands::open_download_url,
+            commands::list_spicetify_versions,
+            commands::probe_spicetify,
             commands::check_spicetify_location,
+            commands::export_diagnostics,
+            commands::get_spicetify_config,
+            commands::set_spicetify_config_value,
             commands::download_update,
             commands::check_for_app_updates,
+            commands::get_release_source,
+            commands::set_release_source,
+            commands::get_release_channel,
+            commands::set_release_channel,
+            commands::get_download_cache,
+            commands::set_download_cache_limit,
+            commands::clear_download_cache,
             commands::download_and_install_update,
+            commands::list_installer_versions,
+            commands::rollback_installer,
             commands::restart_application
         ])
         .run(tauri::generate_context!())
@@ -2,8 +2,4 @@
 
 fn main() {
     spicetify_installer_lib::run();
-    println!("I CHANGED THIS!");
-    tauri::Builder::default()
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
 }
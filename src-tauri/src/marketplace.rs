@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::assets::AssetStatus;
+use crate::commands::{execute_apply_command, execute_backup_command};
+use crate::custom_apps::{self, CustomApp};
+use crate::download_cache::{self, CacheKey, CachedProduct};
+use crate::error::{InstallerError, InstallerResult};
+use crate::process_runner::{check_cancelled, wait_for_cancellation};
+use crate::release_source::ReleaseSource;
+use crate::spicetify_config::{ConfigFile, SpicetifyPaths};
+use crate::spicetify_install::{download_archive, InstallLog};
+use crate::version::is_newer;
+
+/// Folder name in `CustomApps` and entry in `custom_apps`.
+const APP_NAME: &str = "marketplace";
+const ASSET_NAME: &str = "marketplace.zip";
+/// Written next to the app, since the Marketplace build does not record
+/// its own release.
+const VERSION_FILE: &str = "installer-version.txt";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceStatus {
+    pub installed: bool,
+    pub enabled: bool,
+    /// `None` when installed by other means, e.g. the upstream script.
+    pub version: Option<String>,
+    /// `None` when the release server could not be reached.
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub path: Option<String>,
+    /// Why the installed app is invalid, or why it is enabled but missing.
+    pub problem: Option<String>,
+}
+
+/// Where Marketplace stands locally and whether a newer release exists.
+pub(crate) async fn status() -> InstallerResult<MarketplaceStatus> {
+    let app = installed_app(&SpicetifyPaths::resolve()?)?;
+    let latest_version = match fetch_latest_tag().await {
+        Ok(tag) => Some(tag.trim_start_matches('v').to_string()),
+        Err(e) => {
+            eprintln!("Error checking the latest Marketplace release: {}", e);
+            None
+        }
+    };
+
+    let Some(app) = app else {
+        return Ok(MarketplaceStatus {
+            installed: false,
+            enabled: false,
+            version: None,
+            latest_version,
+            update_available: false,
+            path: None,
+            problem: None,
+        });
+    };
+
+    let version = app
+        .path
+        .as_deref()
+        .and_then(|path| read_version(Path::new(path)));
+    let update_available = match (&version, &latest_version) {
+        (Some(current), Some(latest)) => is_newer(latest, current),
+        // Without a recorded version, reinstalling is the only way to update
+        (None, Some(_)) => app.path.is_some(),
+        _ => false,
+    };
+    let problem = match app.status {
+        AssetStatus::Missing => {
+            Some("Enabled in config-xpui.ini but not installed".to_string())
+        }
+        _ => app.problem,
+    };
+
+    Ok(MarketplaceStatus {
+        installed: app.path.is_some(),
+        enabled: app.status == AssetStatus::Enabled,
+        version,
+        latest_version,
+        update_available,
+        path: app.path,
+        problem,
+    })
+}
+
+/// Installs or updates Marketplace from the latest release into
+/// `CustomApps/marketplace`, enables it and applies.
+pub(crate) async fn install(app_handle: &AppHandle) -> InstallerResult<String> {
+    let mut log = InstallLog::new(app_handle);
+
+    log.step(0, "Installing Spicetify Marketplace...");
+    log.step(10, "Resolving the latest Marketplace release...");
+    let tag = fetch_latest_tag().await?;
+    let version = tag.trim_start_matches('v').to_string();
+    log.line(&format!(" > Installing version {}", version));
+
+    log.step(20, &format!("Downloading Marketplace v{}...", version));
+    let (archive_path, cached) = fetch_archive(&tag, &version).await?;
+    log.line(if cached {
+        " > Using cached archive"
+    } else {
+        " > OK"
+    });
+
+    check_cancelled()?;
+    log.step(50, "Installing into CustomApps...");
+    let paths = SpicetifyPaths::resolve()?;
+    custom_apps::install(&paths, &archive_path, Some(APP_NAME), true)?;
+    if let Some(path) = installed_app(&paths)?.and_then(|app| app.path) {
+        fs::write(Path::new(&path).join(VERSION_FILE), &version)
+            .map_err(|e| InstallerError::io("Failed to record the Marketplace version", e))?;
+        log.line(&format!(" > {}", path));
+    }
+
+    log.step(60, "Updating config-xpui.ini...");
+    let mut config = paths.load_config()?;
+    // Marketplace themes rely on both
+    config.set("Setting", "inject_css", "1")?;
+    config.set("Setting", "replace_colors", "1")?;
+    config.save()?;
+    log.line(" > custom_apps, inject_css and replace_colors set");
+
+    apply(app_handle, &mut log, &paths, &config).await?;
+
+    log.step(100, &format!("Marketplace v{} was installed!", version));
+    Ok(log.output)
+}
+
+/// Removes Marketplace without touching other custom apps, then applies.
+pub(crate) async fn uninstall(app_handle: &AppHandle) -> InstallerResult<String> {
+    let mut log = InstallLog::new(app_handle);
+
+    log.step(0, "Removing Spicetify Marketplace...");
+    let paths = SpicetifyPaths::resolve()?;
+    if installed_app(&paths)?.is_none() {
+        return Err(InstallerError::invalid_data("Marketplace is not installed"));
+    }
+    custom_apps::remove(&paths, APP_NAME)?;
+    log.line(" > Removed from CustomApps and custom_apps");
+
+    apply(app_handle, &mut log, &paths, &paths.load_config()?).await?;
+
+    log.step(100, "Marketplace was removed.");
+    Ok(log.output)
+}
+
+/// Runs `spicetify apply`, backing up first on a fresh installation since
+/// apply refuses to run without a backup. Both run the resolved CLI, since
+/// one installed moments ago is not on this process's `PATH`.
+async fn apply(
+    app_handle: &AppHandle,
+    log: &mut InstallLog,
+    paths: &SpicetifyPaths,
+    config: &ConfigFile,
+) -> InstallerResult<()> {
+    check_cancelled()?;
+    let backed_up = config
+        .get("Backup", "version")
+        .is_some_and(|version| !version.is_empty());
+    if !backed_up {
+        log.step(70, "Backing up Spotify...");
+        log.output
+            .push_str(&execute_backup_command(app_handle.clone(), paths.binary.clone()).await?);
+    }
+
+    log.step(85, "Applying Spicetify...");
+    log.output
+        .push_str(&execute_apply_command(app_handle.clone(), paths.binary.clone()).await?);
+    Ok(())
+}
+
+fn installed_app(paths: &SpicetifyPaths) -> InstallerResult<Option<CustomApp>> {
+    Ok(custom_apps::list(paths)?
+        .into_iter()
+        .find(|app| app.name == APP_NAME && !app.bundled))
+}
+
+fn read_version(app_dir: &Path) -> Option<String> {
+    fs::read_to_string(app_dir.join(VERSION_FILE))
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+}
+
+async fn fetch_latest_tag() -> InstallerResult<String> {
+    let release = ReleaseSource::current()
+        .marketplace
+        .fetch_latest_release("Spicetify-Installer")
+        .await?;
+    release["tag_name"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| InstallerError::invalid_data("Missing tag_name in release response"))
+}
+
+/// The release zip for `tag` and whether it came from the download cache.
+async fn fetch_archive(tag: &str, version: &str) -> InstallerResult<(PathBuf, bool)> {
+    let key = CacheKey::new(CachedProduct::Marketplace, version, ASSET_NAME);
+    if let Some(path) = download_cache::lookup(&key) {
+        return Ok((path, true));
+    }
+
+    let url = ReleaseSource::current()
+        .marketplace
+        .download_url(tag, ASSET_NAME);
+    let download_path = env::temp_dir().join(ASSET_NAME);
+
+    check_cancelled()?;
+    tokio::select! {
+        result = download_archive(&url, &download_path) => result?,
+        _ = wait_for_cancellation() => {
+            let _ = fs::remove_file(&download_path);
+            return Err(InstallerError::cancelled());
+        }
+    }
+
+    let path = download_cache::store(&key, &download_path).inspect_err(|_| {
+        let _ = fs::remove_file(&download_path);
+    })?;
+    Ok((path, false))
+}
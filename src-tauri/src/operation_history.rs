@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, EventId, Listener, Manager};
+
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::spicetify_probe::SpicetifyProbe;
+use crate::spotify;
+
+const HISTORY_FILE: &str = "operation_history.jsonl";
+/// Older records are dropped once the file holds this many.
+const MAX_RECORDS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationStatus {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// One line of captured output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptLine {
+    pub stream: String,
+    pub line: String,
+}
+
+/// What happened during one operation, without its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSummary {
+    pub id: String,
+    /// `Operation::name`, e.g. `install`.
+    pub operation: String,
+    /// Milliseconds since the Unix epoch.
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub status: OperationStatus,
+    /// Exit code of the last script the operation ran; `None` for native
+    /// pipelines and scripts that never exited normally.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Versions found once the operation finished.
+    pub spicetify_version: Option<String>,
+    pub spotify_version: Option<String>,
+}
+
+/// A history record: the summary plus everything the operation printed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationRecord {
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+    pub transcript: Vec<TranscriptLine>,
+}
+
+static HISTORY_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Resolves the history file inside the app data directory.
+pub(crate) fn init(app_handle: &AppHandle) -> InstallerResult<()> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| {
+        InstallerError::invalid_data("Failed to resolve the app data directory").with_detail(e)
+    })?;
+    *HISTORY_PATH.lock().unwrap() = Some(data_dir.join(HISTORY_FILE));
+    Ok(())
+}
+
+/// Captures the `operation_output` and `script_exit` events of a running
+/// operation.
+pub(crate) struct Recorder {
+    app_handle: AppHandle,
+    listeners: [EventId; 2],
+    operation: &'static str,
+    started_at: u64,
+    transcript: Arc<Mutex<Vec<TranscriptLine>>>,
+    exit_code: Arc<Mutex<Option<i32>>>,
+}
+
+impl Recorder {
+    pub(crate) fn start(app_handle: &AppHandle, operation: &'static str) -> Self {
+        let transcript = Arc::new(Mutex::new(Vec::new()));
+        let lines = transcript.clone();
+        let output_listener = app_handle.listen_any("operation_output", move |event| {
+            if let Ok(line) = serde_json::from_str::<TranscriptLine>(event.payload()) {
+                lines.lock().unwrap().push(line);
+            }
+        });
+
+        let exit_code = Arc::new(Mutex::new(None));
+        let last_code = exit_code.clone();
+        let exit_listener = app_handle.listen_any("script_exit", move |event| {
+            if let Ok(code) = serde_json::from_str::<Option<i32>>(event.payload()) {
+                *last_code.lock().unwrap() = code;
+            }
+        });
+
+        Self {
+            app_handle: app_handle.clone(),
+            listeners: [output_listener, exit_listener],
+            operation,
+            started_at: now_millis(),
+            transcript,
+            exit_code,
+        }
+    }
+
+    /// Stops capturing and appends the record to the history file. Failing
+    /// to write history never fails the operation itself.
+    pub(crate) fn finish(self, result: &InstallerResult<String>) {
+        for listener in self.listeners {
+            self.app_handle.unlisten(listener);
+        }
+
+        let mut transcript = std::mem::take(&mut *self.transcript.lock().unwrap());
+        // Some runners only return their output instead of streaming it
+        if let (true, Ok(output)) = (transcript.is_empty(), result) {
+            transcript.extend(output.lines().map(|line| TranscriptLine {
+                stream: "stdout".to_string(),
+                line: line.to_string(),
+            }));
+        }
+
+        let (status, error) = match result {
+            Ok(_) => (OperationStatus::Succeeded, None),
+            Err(e) if e.kind == ErrorKind::Cancelled => (OperationStatus::Cancelled, None),
+            Err(e) => (OperationStatus::Failed, Some(e.to_string())),
+        };
+
+        let record = OperationRecord {
+            summary: OperationSummary {
+                id: format!("{}-{}", self.started_at, self.operation),
+                operation: self.operation.to_string(),
+                started_at: self.started_at,
+                ended_at: now_millis(),
+                status,
+                exit_code: *self.exit_code.lock().unwrap(),
+                error,
+                spicetify_version: SpicetifyProbe::run().version,
+                spotify_version: spotify::installed_version(),
+            },
+            transcript,
+        };
+
+        if let Err(e) = append(&record) {
+            eprintln!("Failed to record operation history: {}", e);
+        }
+    }
+}
+
+/// Recorded operations, newest first, without their transcripts.
+pub(crate) fn summaries() -> InstallerResult<Vec<OperationSummary>> {
+    let mut summaries: Vec<OperationSummary> = read_records()?
+        .into_iter()
+        .map(|record| record.summary)
+        .collect();
+    summaries.reverse();
+    Ok(summaries)
+}
+
+/// The `limit` most recent records, newest first, with transcripts.
+pub(crate) fn recent(limit: usize) -> InstallerResult<Vec<OperationRecord>> {
+    Ok(read_records()?.into_iter().rev().take(limit).collect())
+}
+
+pub(crate) fn record(id: &str) -> InstallerResult<OperationRecord> {
+    read_records()?
+        .into_iter()
+        .find(|record| record.summary.id == id)
+        .ok_or_else(|| {
+            InstallerError::invalid_data(format!("No operation with id {} in the history", id))
+        })
+}
+
+fn history_path() -> InstallerResult<PathBuf> {
+    HISTORY_PATH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| InstallerError::invalid_data("Operation history has not been set up"))
+}
+
+/// Records in the order they were written. Lines that fail to parse, such as
+/// one cut short by a crash, are skipped.
+fn read_records() -> InstallerResult<Vec<OperationRecord>> {
+    let path = history_path()?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(InstallerError::io("Failed to read operation history", e)),
+    };
+
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn append(record: &OperationRecord) -> InstallerResult<()> {
+    let path = history_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| InstallerError::io("Failed to create app data directory", e))?;
+    }
+
+    let line = serde_json::to_string(record).map_err(|e| {
+        InstallerError::invalid_data("Failed to serialize operation record").with_detail(e)
+    })?;
+
+    let mut records = read_records()?;
+    if records.len() >= MAX_RECORDS {
+        records.drain(..=records.len() - MAX_RECORDS);
+        let mut contents = String::new();
+        for record in &records {
+            if let Ok(existing) = serde_json::to_string(record) {
+                contents.push_str(&existing);
+                contents.push('\n');
+            }
+        }
+        contents.push_str(&line);
+        contents.push('\n');
+        return fs::write(&path, contents)
+            .map_err(|e| InstallerError::io("Failed to write operation history", e));
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| InstallerError::io("Failed to open operation history", e))?;
+    writeln!(file, "{}", line)
+        .map_err(|e| InstallerError::io("Failed to write operation history", e))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{
+    execute_apply_command, execute_backup_command, execute_repair_command,
+    execute_restore_command, execute_uninstall_command, install_spicetify_direct,
+    install_spicetify_from_archive,
+};
+use crate::error::{InstallerError, InstallerResult};
+use crate::marketplace;
+use crate::operation_history::Recorder;
+use crate::process_runner::begin_operation;
+use crate::spicetify_install::upgrade_spicetify;
+
+/// A Spicetify operation the frontend can request. Each variant maps to one
+/// dedicated runner instead of an arbitrary PowerShell string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Operation {
+    Install {
+        /// Exact Spicetify version to install; `None` installs the latest.
+        #[serde(default)]
+        version: Option<String>,
+    },
+    /// Installs from a local release archive without network access.
+    InstallFromArchive {
+        #[serde(rename = "archivePath")]
+        archive_path: String,
+    },
+    /// Replaces an installed Spicetify CLI with the latest release.
+    Upgrade,
+    Uninstall {
+        #[serde(rename = "keepThemes", default)]
+        keep_themes: bool,
+    },
+    Repair,
+    Backup,
+    Apply,
+    Restore,
+    /// Installs or updates Marketplace from its latest release.
+    InstallMarketplace,
+    UninstallMarketplace,
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Install { .. } => "install",
+            Operation::InstallFromArchive { .. } => "install_from_archive",
+            Operation::Upgrade => "upgrade",
+            Operation::Uninstall { .. } => "uninstall",
+            Operation::Repair => "repair",
+            Operation::Backup => "backup",
+            Operation::Apply => "apply",
+            Operation::Restore => "restore",
+            Operation::InstallMarketplace => "install_marketplace",
+            Operation::UninstallMarketplace => "uninstall_marketplace",
+        }
+    }
+
+    pub async fn run(self, app_handle: AppHandle) -> InstallerResult<String> {
+        eprintln!("Running operation: {:?}", self);
+
+        let guard = begin_operation()?;
+        let name = self.name();
+        let recorder = Recorder::start(&app_handle, name);
+
+        let result = match self {
+            Operation::Install { version } => {
+                install_spicetify_direct(app_handle.clone(), version).await
+            }
+            Operation::InstallFromArchive { archive_path } => {
+                install_spicetify_from_archive(app_handle.clone(), archive_path).await
+            }
+            Operation::Upgrade => upgrade_spicetify(app_handle.clone()).await,
+            Operation::Uninstall { keep_themes } => {
+                execute_uninstall_command(app_handle.clone(), keep_themes).await
+            }
+            Operation::Repair => execute_repair_command(app_handle.clone()).await,
+            Operation::Backup => execute_backup_command(app_handle.clone(), None).await,
+            Operation::Apply => execute_apply_command(app_handle.clone(), None).await,
+            Operation::Restore => execute_restore_command(app_handle.clone()).await,
+            Operation::InstallMarketplace => marketplace::install(&app_handle).await,
+            Operation::UninstallMarketplace => marketplace::uninstall(&app_handle).await,
+        };
+
+        // A cancel that lands after the runner finished does not undo its work
+        let result = match result {
+            Err(_) if guard.token().is_cancelled() => {
+                eprintln!("Operation {} was cancelled", name);
+                let _ = app_handle.emit("operation_cancelled", name);
+                Err(InstallerError::cancelled())
+            }
+            result => result,
+        };
+
+        recorder.finish(&result);
+        result
+    }
+}
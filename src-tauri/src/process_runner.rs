@@ -0,0 +1,415 @@
+use serde::Serialize;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, Notify};
+
+use crate::error::{InstallerError, InstallerResult};
+
+static ACTIVE_OPERATION: Mutex<Option<CancelToken>> = Mutex::new(None);
+
+/// Shared flag that lets `cancel_operation` interrupt a running operation.
+#[derive(Clone, Default)]
+pub(crate) struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl CancelToken {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub(crate) async fn cancelled(&self) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Marks an operation as running until dropped. Only one operation may run
+/// at a time.
+pub(crate) struct OperationGuard {
+    token: CancelToken,
+}
+
+impl OperationGuard {
+    pub(crate) fn token(&self) -> &CancelToken {
+        &self.token
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_OPERATION.lock().unwrap();
+        if active
+            .as_ref()
+            .is_some_and(|token| Arc::ptr_eq(&token.cancelled, &self.token.cancelled))
+        {
+            *active = None;
+        }
+    }
+}
+
+pub(crate) fn begin_operation() -> InstallerResult<OperationGuard> {
+    let mut active = ACTIVE_OPERATION.lock().unwrap();
+    if active.is_some() {
+        return Err(InstallerError::busy("Another operation is already running"));
+    }
+
+    let token = CancelToken::default();
+    *active = Some(token.clone());
+    Ok(OperationGuard { token })
+}
+
+/// Requests cancellation of the running operation. Returns `false` when
+/// nothing is running.
+pub(crate) fn cancel_active_operation() -> bool {
+    match ACTIVE_OPERATION.lock().unwrap().as_ref() {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+fn active_cancel_token() -> Option<CancelToken> {
+    ACTIVE_OPERATION.lock().unwrap().clone()
+}
+
+/// Returns an error once the running operation has been cancelled, for
+/// native pipelines to call between steps.
+pub(crate) fn check_cancelled() -> InstallerResult<()> {
+    match active_cancel_token() {
+        Some(token) if token.is_cancelled() => Err(InstallerError::cancelled()),
+        _ => Ok(()),
+    }
+}
+
+/// Resolves when the running operation is cancelled; never resolves when no
+/// operation is active.
+pub(crate) async fn wait_for_cancellation() {
+    match active_cancel_token() {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Builds a `Command` that does not flash a console window on Windows.
+pub(crate) fn hidden_command<S: AsRef<OsStr>>(program: S) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut command = std::process::Command::new(program);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// A PowerShell script to run with live output and progress.
+pub(crate) struct ScriptRun<'a> {
+    /// Short name used for the temp script file and log lines.
+    pub name: &'a str,
+    pub script: &'a str,
+    /// Extra arguments passed to the script after `-File`.
+    pub args: Vec<String>,
+    /// Number of `Step N:` markers the script prints.
+    pub total_steps: u32,
+    /// Files the script creates in temp that must not outlive it.
+    pub temp_files: Vec<PathBuf>,
+}
+
+pub(crate) struct ScriptOutput {
+    pub success: bool,
+    pub status: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputLine {
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Turns `Step N:` markers in script output into monotonic percentages.
+struct ProgressTracker {
+    total_steps: u32,
+    current: u32,
+}
+
+impl ProgressTracker {
+    fn new(total_steps: u32) -> Self {
+        Self {
+            total_steps: total_steps.max(1),
+            current: 0,
+        }
+    }
+
+    /// Returns the new percentage when `line` starts a later step.
+    fn observe(&mut self, line: &str) -> Option<u32> {
+        let step = parse_step_marker(line)?;
+        let step = step.min(self.total_steps);
+        // Leave headroom at both ends: 5% once started, 100% only on exit.
+        let progress = 5 + (step - 1) * 90 / self.total_steps;
+        if progress > self.current {
+            self.current = progress;
+            Some(progress)
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_step_marker(line: &str) -> Option<u32> {
+    let rest = line.trim_start().strip_prefix("Step ")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() || !rest[digits.len()..].starts_with(':') {
+        return None;
+    }
+    digits.parse().ok().filter(|step| *step > 0)
+}
+
+/// Writes `run.script` to a temp file, runs it with PowerShell and streams
+/// every output line to the UI as `operation_output`. Progress is reported
+/// on `progress_update` as the script reaches each step.
+pub(crate) async fn run_powershell_script(
+    app_handle: &AppHandle,
+    run: ScriptRun<'_>,
+) -> InstallerResult<ScriptOutput> {
+    let script_path = env::temp_dir().join(format!("spicetify_{}.ps1", run.name));
+
+    fs::write(&script_path, run.script)
+        .map_err(|e| InstallerError::io(format!("Failed to write {} script", run.name), e))?;
+
+    let result = stream_script(app_handle, &script_path.to_string_lossy(), &run).await;
+
+    let _ = fs::remove_file(&script_path);
+    for temp_file in &run.temp_files {
+        let _ = fs::remove_file(temp_file);
+    }
+    result
+}
+
+async fn stream_script(
+    app_handle: &AppHandle,
+    script_path: &str,
+    run: &ScriptRun<'_>,
+) -> InstallerResult<ScriptOutput> {
+    app_handle.emit("progress_update", 0).unwrap();
+
+    let mut command = Command::new("powershell");
+    command
+        .args(["-ExecutionPolicy", "Bypass", "-File", script_path])
+        .args(&run.args);
+
+    let child = spawn_streamed(command).map_err(|e| {
+        InstallerError::io(format!("Failed to execute {} script", run.name), e)
+            .with_remediation("Make sure Windows PowerShell is installed and available.")
+    })?;
+
+    stream_output(app_handle, run.name, child, run.total_steps).await
+}
+
+/// Runs `program` with `args` directly, without a shell, streaming its
+/// output like `run_powershell_script`. Works where PowerShell is missing.
+pub(crate) async fn run_program(
+    app_handle: &AppHandle,
+    name: &str,
+    program: &Path,
+    args: &[&str],
+) -> InstallerResult<ScriptOutput> {
+    app_handle.emit("progress_update", 0).unwrap();
+
+    let mut command = Command::new(program);
+    // Nothing can answer a prompt, so fail instead of waiting on one
+    command.args(args).stdin(Stdio::null());
+
+    let child = spawn_streamed(command)
+        .map_err(|e| InstallerError::io(format!("Failed to run {}", program.display()), e))?;
+
+    stream_output(app_handle, name, child, 1).await
+}
+
+fn spawn_streamed(mut command: Command) -> std::io::Result<Child> {
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    // Own process group so cancellation can take down the whole tree.
+    #[cfg(unix)]
+    command.process_group(0);
+
+    command.spawn()
+}
+
+async fn stream_output(
+    app_handle: &AppHandle,
+    name: &str,
+    mut child: Child,
+    total_steps: u32,
+) -> InstallerResult<ScriptOutput> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<OutputLine>();
+
+    if let Some(stdout) = child.stdout.take() {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = sender.send(OutputLine { stream: "stdout", line });
+            }
+        });
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = sender.send(OutputLine { stream: "stderr", line });
+            }
+        });
+    }
+
+    drop(sender);
+
+    let mut tracker = ProgressTracker::new(total_steps);
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+
+    loop {
+        let output_line = tokio::select! {
+            line = receiver.recv() => match line {
+                Some(line) => line,
+                None => break,
+            },
+            _ = wait_for_cancellation() => {
+                eprintln!("[{}] Cancelling...", name);
+                kill_process_tree(&mut child).await;
+                return Err(InstallerError::cancelled());
+            }
+        };
+
+        if output_line.stream == "stdout" {
+            if let Some(progress) = tracker.observe(&output_line.line) {
+                app_handle.emit("progress_update", progress).unwrap();
+            }
+            stdout.push_str(&output_line.line);
+            stdout.push('\n');
+        } else {
+            stderr.push_str(&output_line.line);
+            stderr.push('\n');
+        }
+
+        let _ = app_handle.emit("operation_output", &output_line);
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| InstallerError::io(format!("Failed to wait for {}", name), e))?;
+
+    app_handle.emit("progress_update", 100).unwrap();
+    // `None` when the script was killed by a signal
+    let _ = app_handle.emit("script_exit", status.code());
+
+    Ok(ScriptOutput {
+        success: status.success(),
+        status: status.to_string(),
+        stdout,
+        stderr,
+    })
+}
+
+async fn kill_process_tree(child: &mut Child) {
+    if let Some(pid) = child.id() {
+        #[cfg(windows)]
+        let _ = hidden_command("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+
+        #[cfg(unix)]
+        let _ = std::process::Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", pid)])
+            .output();
+    }
+
+    let _ = child.kill().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_markers() {
+        let cases = [
+            ("Step 1: Restoring Spotify...", Some(1)),
+            ("Step 12: Done", Some(12)),
+            ("  Step 2: Indented", Some(2)),
+            ("Step 3:", Some(3)),
+            ("Step 0: Not a step", None),
+            ("Step : Missing number", None),
+            ("Step 4 Missing colon", None),
+            ("Step 5a: Trailing letter", None),
+            ("step 1: Lowercase", None),
+            ("Next Step 1: Not at the start", None),
+            ("", None),
+        ];
+
+        for (line, step) in cases {
+            assert_eq!(parse_step_marker(line), step, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn progress_is_monotonic() {
+        // (total_steps, lines, expected progress per line)
+        type Case<'a> = (u32, &'a [&'a str], &'a [Option<u32>]);
+        let cases: [Case; 4] = [
+            (
+                3,
+                &["Step 1: a", "output", "Step 2: b", "Step 3: c"],
+                &[Some(5), None, Some(35), Some(65)],
+            ),
+            (
+                3,
+                &["Step 2: b", "Step 1: a", "Step 2: b again"],
+                &[Some(35), None, None],
+            ),
+            (2, &["Step 1: a", "Step 9: past the end"], &[Some(5), Some(50)]),
+            (0, &["Step 1: a", "Step 2: b"], &[Some(5), None]),
+        ];
+
+        for (total_steps, lines, expected) in cases {
+            let mut tracker = ProgressTracker::new(total_steps);
+            let observed: Vec<Option<u32>> =
+                lines.iter().map(|line| tracker.observe(line)).collect();
+            assert_eq!(observed, expected, "{} steps: {:?}", total_steps, lines);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::custom_apps;
+use crate::error::{InstallerError, InstallerResult};
+use crate::extensions;
+use crate::spicetify_config::SpicetifyPaths;
+use crate::themes;
+
+const PROFILES_FILE: &str = "profiles.json";
+/// Sections a profile captures. `Backup` and `Launch` describe this
+/// machine's Spotify rather than its look.
+const CAPTURED_SECTIONS: [&str; 3] = ["Setting", "Preprocesses", "AdditionalOptions"];
+/// Captured sections' keys that point at local files.
+const MACHINE_KEYS: [&str; 2] = ["spotify_path", "prefs_path"];
+/// Kept in their own profile fields instead of `values`.
+const THEME_KEYS: [(&str, &str); 4] = [
+    ("Setting", "current_theme"),
+    ("Setting", "color_scheme"),
+    ("AdditionalOptions", "extensions"),
+    ("AdditionalOptions", "custom_apps"),
+];
+
+/// One `config-xpui.ini` value restored by a profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileValue {
+    pub section: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// A named snapshot of the Spicetify customization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupProfile {
+    pub name: String,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    pub current_theme: String,
+    pub color_scheme: String,
+    pub extensions: Vec<String>,
+    pub custom_apps: Vec<String>,
+    /// Every other captured setting.
+    pub values: Vec<ProfileValue>,
+}
+
+static PROFILES_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Resolves the profiles file inside the app config directory.
+pub(crate) fn init(app_handle: &AppHandle) -> InstallerResult<()> {
+    let config_dir = app_handle.path().app_config_dir().map_err(|e| {
+        InstallerError::invalid_data("Failed to resolve the app config directory").with_detail(e)
+    })?;
+    *PROFILES_PATH.lock().unwrap() = Some(config_dir.join(PROFILES_FILE));
+    Ok(())
+}
+
+/// Saved profiles, sorted by name.
+pub(crate) fn list() -> InstallerResult<Vec<SetupProfile>> {
+    let path = profiles_path()?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(InstallerError::io("Failed to read profiles", e)),
+    };
+
+    let mut profiles: Vec<SetupProfile> = serde_json::from_str(&contents).map_err(|e| {
+        InstallerError::invalid_data("Profiles file is not valid JSON")
+            .with_detail(e)
+            .with_remediation(format!("Fix or delete {}.", path.display()))
+    })?;
+    profiles.sort_by_key(|profile| profile.name.to_lowercase());
+    Ok(profiles)
+}
+
+/// Captures the current config as `name`, replacing a profile of the same
+/// name.
+pub(crate) fn save(paths: &SpicetifyPaths, name: &str) -> InstallerResult<Vec<SetupProfile>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(InstallerError::invalid_data("Profile name cannot be empty"));
+    }
+
+    let config = paths.load_config()?;
+    let values = config
+        .to_view()
+        .sections
+        .into_iter()
+        .filter(|section| CAPTURED_SECTIONS.contains(&section.name.as_str()))
+        .flat_map(|section| {
+            section.entries.into_iter().map(move |entry| ProfileValue {
+                section: section.name.clone(),
+                key: entry.key,
+                value: entry.value,
+            })
+        })
+        .filter(|value| {
+            !MACHINE_KEYS.contains(&value.key.as_str())
+                && !THEME_KEYS.contains(&(value.section.as_str(), value.key.as_str()))
+        })
+        .collect();
+
+    let profile = SetupProfile {
+        name: name.to_string(),
+        saved_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+        current_theme: config.get("Setting", "current_theme").unwrap_or_default(),
+        color_scheme: config.get("Setting", "color_scheme").unwrap_or_default(),
+        extensions: config.get_list("AdditionalOptions", "extensions"),
+        custom_apps: config.get_list("AdditionalOptions", "custom_apps"),
+        values,
+    };
+
+    let mut profiles = list()?;
+    profiles.retain(|existing| existing.name != profile.name);
+    profiles.push(profile);
+    write(&profiles)?;
+    eprintln!("Saved profile {}", name);
+
+    list()
+}
+
+/// Writes `name`'s values into `config-xpui.ini`. Fails before touching
+/// the config when the profile's theme, extensions or apps are not installed.
+pub(crate) fn activate(paths: &SpicetifyPaths, name: &str) -> InstallerResult<SetupProfile> {
+    let profile = find(name)?;
+
+    let mut missing = Vec::new();
+    if !profile.current_theme.is_empty()
+        && !themes::list(paths)?
+            .themes
+            .iter()
+            .any(|theme| theme.name == profile.current_theme)
+    {
+        missing.push(format!("theme {}", profile.current_theme));
+    }
+    let installed_extensions = extensions::list(paths)?;
+    for extension in &profile.extensions {
+        if !installed_extensions
+            .iter()
+            .any(|installed| &installed.name == extension && installed.path.is_some())
+        {
+            missing.push(format!("extension {}", extension));
+        }
+    }
+    let installed_apps = custom_apps::list(paths)?;
+    for app in &profile.custom_apps {
+        if !installed_apps
+            .iter()
+            .any(|installed| &installed.name == app && installed.path.is_some())
+        {
+            missing.push(format!("custom app {}", app));
+        }
+    }
+    if !missing.is_empty() {
+        return Err(InstallerError::invalid_data(format!(
+            "Profile {} needs items that are not installed: {}",
+            name,
+            missing.join(", ")
+        ))
+        .with_remediation("Install them, or save the profile again without them."));
+    }
+
+    let mut config = paths.load_config()?;
+    for value in &profile.values {
+        config.set(&value.section, &value.key, &value.value)?;
+    }
+    config.set("Setting", "current_theme", &profile.current_theme)?;
+    config.set("Setting", "color_scheme", &profile.color_scheme)?;
+    config.set(
+        "AdditionalOptions",
+        "extensions",
+        &profile.extensions.join("|"),
+    )?;
+    config.set(
+        "AdditionalOptions",
+        "custom_apps",
+        &profile.custom_apps.join("|"),
+    )?;
+    config.save()?;
+    eprintln!("Activated profile {}", name);
+
+    Ok(profile)
+}
+
+pub(crate) fn delete(name: &str) -> InstallerResult<Vec<SetupProfile>> {
+    find(name)?;
+    let mut profiles = list()?;
+    profiles.retain(|profile| profile.name != name);
+    write(&profiles)?;
+    eprintln!("Deleted profile {}", name);
+    Ok(profiles)
+}
+
+fn find(name: &str) -> InstallerResult<SetupProfile> {
+    list()?
+        .into_iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| InstallerError::invalid_data(format!("No profile named {}", name)))
+}
+
+fn profiles_path() -> InstallerResult<PathBuf> {
+    PROFILES_PATH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| InstallerError::invalid_data("Profiles have not been set up"))
+}
+
+fn write(profiles: &[SetupProfile]) -> InstallerResult<()> {
+    let path = profiles_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| InstallerError::io("Failed to create app config directory", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(profiles)
+        .map_err(|e| InstallerError::invalid_data("Failed to serialize profiles").with_detail(e))?;
+    fs::write(&path, contents).map_err(|e| InstallerError::io("Failed to write profiles", e))
+}
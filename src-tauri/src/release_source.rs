@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use crate::error::{InstallerError, InstallerResult};
+use crate::settings;
+use crate::version::Version;
+
+const GITHUB_API_BASE_URL: &str = "https://api.github.com";
+const GITHUB_BASE_URL: &str = "https://github.com";
+
+/// Overrides the API base URL of every repository, e.g. to point at a
+/// local HTTP stand-in during tests.
+const API_BASE_URL_ENV: &str = "SPICETIFY_INSTALLER_API_BASE_URL";
+/// Overrides the download base URL of every repository.
+const DOWNLOAD_BASE_URL_ENV: &str = "SPICETIFY_INSTALLER_DOWNLOAD_BASE_URL";
+
+/// Where the releases of one repository are published. Any GitHub-compatible
+/// host works: github.com, GitHub Enterprise or an internal mirror.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoEndpoint {
+    /// REST API root, e.g. `https://api.github.com` or
+    /// `https://github.example.com/api/v3`.
+    #[serde(rename = "apiBaseUrl")]
+    pub api_base_url: String,
+    /// Web root that serves `<repo>/releases/download/...`.
+    #[serde(rename = "downloadBaseUrl")]
+    pub download_base_url: String,
+    /// `owner/name` of the repository.
+    pub repo: String,
+}
+
+impl RepoEndpoint {
+    fn github(repo: &str) -> Self {
+        Self {
+            api_base_url: GITHUB_API_BASE_URL.to_string(),
+            download_base_url: GITHUB_BASE_URL.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    pub fn latest_release_url(&self) -> String {
+        format!(
+            "{}/repos/{}/releases/latest",
+            self.api_base_url.trim_end_matches('/'),
+            self.repo
+        )
+    }
+
+    pub fn releases_url(&self) -> String {
+        format!(
+            "{}/repos/{}/releases",
+            self.api_base_url.trim_end_matches('/'),
+            self.repo
+        )
+    }
+
+    pub fn download_url(&self, tag: &str, asset_name: &str) -> String {
+        format!(
+            "{}/{}/releases/download/{}/{}",
+            self.download_base_url.trim_end_matches('/'),
+            self.repo,
+            tag,
+            asset_name
+        )
+    }
+
+    /// Human-facing page of the latest release.
+    pub fn latest_release_page_url(&self) -> String {
+        format!(
+            "{}/{}/releases/latest",
+            self.download_base_url.trim_end_matches('/'),
+            self.repo
+        )
+    }
+
+    fn validate(&self, label: &str) -> InstallerResult<()> {
+        for url in [&self.api_base_url, &self.download_base_url] {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(InstallerError::invalid_data(format!(
+                    "Invalid {} release URL: {}",
+                    label, url
+                ))
+                .with_remediation("Use a full URL starting with http:// or https://."));
+            }
+        }
+
+        let mut parts = self.repo.split('/');
+        let valid_repo = matches!(
+            (parts.next(), parts.next(), parts.next()),
+            (Some(owner), Some(name), None) if !owner.is_empty() && !name.is_empty()
+        );
+        if !valid_repo {
+            return Err(InstallerError::invalid_data(format!(
+                "Invalid {} repository: {}",
+                label, self.repo
+            ))
+            .with_remediation("Use the owner/name form, e.g. spicetify/cli."));
+        }
+
+        Ok(())
+    }
+
+    /// Fetches and parses the latest release metadata.
+    pub async fn fetch_latest_release(
+        &self,
+        user_agent: &str,
+    ) -> InstallerResult<serde_json::Value> {
+        fetch_json(&self.latest_release_url(), user_agent).await
+    }
+}
+
+/// Release endpoints for the installer itself, Spicetify and Marketplace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseSource {
+    pub installer: RepoEndpoint,
+    pub spicetify: RepoEndpoint,
+    /// Missing from settings written before Marketplace was installed natively.
+    #[serde(default = "default_marketplace_endpoint")]
+    pub marketplace: RepoEndpoint,
+}
+
+fn default_marketplace_endpoint() -> RepoEndpoint {
+    RepoEndpoint::github("spicetify/marketplace")
+}
+
+impl Default for ReleaseSource {
+    fn default() -> Self {
+        Self {
+            installer: RepoEndpoint::github("FIREPAWER07/SpicetifyInstaller"),
+            spicetify: RepoEndpoint::github("spicetify/cli"),
+            marketplace: default_marketplace_endpoint(),
+        }
+    }
+}
+
+impl ReleaseSource {
+    /// The configured source with environment overrides applied.
+    pub fn current() -> Self {
+        let mut source = settings::current().release_source;
+
+        if let Ok(api_base_url) = env::var(API_BASE_URL_ENV) {
+            source.installer.api_base_url = api_base_url.clone();
+            source.spicetify.api_base_url = api_base_url.clone();
+            source.marketplace.api_base_url = api_base_url;
+        }
+        if let Ok(download_base_url) = env::var(DOWNLOAD_BASE_URL_ENV) {
+            source.installer.download_base_url = download_base_url.clone();
+            source.spicetify.download_base_url = download_base_url.clone();
+            source.marketplace.download_base_url = download_base_url;
+        }
+
+        source
+    }
+
+    /// Rejects endpoints that could not produce a usable URL.
+    pub fn validate(&self) -> InstallerResult<()> {
+        self.installer.validate("installer")?;
+        self.spicetify.validate("Spicetify")?;
+        self.marketplace.validate("Marketplace")
+    }
+}
+
+/// Which installer builds a user is offered. Each channel includes the more
+/// stable ones: beta testers also get stable releases, alpha gets everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+    Alpha,
+}
+
+impl ReleaseChannel {
+    /// Channel a release belongs to, from its tag and GitHub prerelease flag.
+    /// `-beta` and `-rc` tags are beta; any other prerelease is alpha. A
+    /// release flagged as prerelease without a tag suffix counts as beta.
+    pub fn of(version: &Version, prerelease_flag: bool) -> Self {
+        match version.prerelease_label() {
+            Some(label) if label.eq_ignore_ascii_case("beta") || label.eq_ignore_ascii_case("rc") => {
+                ReleaseChannel::Beta
+            }
+            Some(_) => ReleaseChannel::Alpha,
+            None if version.is_prerelease() => ReleaseChannel::Alpha,
+            None if prerelease_flag => ReleaseChannel::Beta,
+            None => ReleaseChannel::Stable,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReleaseChannel::Stable => "stable",
+            ReleaseChannel::Beta => "beta",
+            ReleaseChannel::Alpha => "alpha",
+        }
+    }
+
+    pub fn includes(self, other: ReleaseChannel) -> bool {
+        other <= self
+    }
+}
+
+/// A published release chosen for a channel.
+pub(crate) struct ChannelRelease {
+    pub version: Version,
+    pub channel: ReleaseChannel,
+    pub release: serde_json::Value,
+}
+
+/// Lists the releases of `endpoint` and picks the newest one that `channel`
+/// accepts. Drafts and tags that are not versions are skipped.
+pub(crate) async fn newest_release(
+    endpoint: &RepoEndpoint,
+    channel: ReleaseChannel,
+    user_agent: &str,
+) -> InstallerResult<ChannelRelease> {
+    let releases = fetch_json(&endpoint.releases_url(), user_agent).await?;
+    let releases = releases
+        .as_array()
+        .ok_or_else(|| InstallerError::invalid_data("Release list is not an array"))?;
+
+    releases
+        .iter()
+        .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+        .filter_map(|release| {
+            let version = Version::parse(release["tag_name"].as_str()?)?;
+            let release_channel =
+                ReleaseChannel::of(&version, release["prerelease"].as_bool().unwrap_or(false));
+            channel.includes(release_channel).then(|| ChannelRelease {
+                version,
+                channel: release_channel,
+                release: release.clone(),
+            })
+        })
+        .max_by(|a, b| a.version.cmp(&b.version))
+        .ok_or_else(|| {
+            InstallerError::invalid_data(format!(
+                "No {} release found for {}",
+                channel.as_str(),
+                endpoint.repo
+            ))
+        })
+}
+
+/// GETs `url` and parses the body as JSON, mapping failures to
+/// `InstallerError`s.
+pub(crate) async fn fetch_json(url: &str, user_agent: &str) -> InstallerResult<serde_json::Value> {
+    get(url, user_agent)
+        .await?
+        .json()
+        .await
+        .map_err(|e| InstallerError::network("Failed to parse release response", e))
+}
+
+/// GETs `url` and returns the body as text, e.g. a checksum file.
+pub(crate) async fn fetch_text(url: &str, user_agent: &str) -> InstallerResult<String> {
+    get(url, user_agent)
+        .await?
+        .text()
+        .await
+        .map_err(|e| InstallerError::network(format!("Failed to read {}", url), e))
+}
+
+async fn get(url: &str, user_agent: &str) -> InstallerResult<reqwest::Response> {
+    let client = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .build()
+        .map_err(|e| InstallerError::network("Failed to create HTTP client", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| InstallerError::network(format!("Failed to fetch {}", url), e))?;
+
+    if !response.status().is_success() {
+        return Err(InstallerError::http_status(
+            format!("Release server rejected the request to {}", url),
+            &response,
+        ));
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{InstallerError, InstallerResult};
+use crate::release_source::{ReleaseChannel, ReleaseSource};
+
+const SETTINGS_FILE: &str = "settings.json";
+const DEFAULT_DOWNLOAD_CACHE_LIMIT_MB: u64 = 512;
+
+/// User preferences persisted as JSON in the app config directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    #[serde(rename = "releaseSource")]
+    pub release_source: ReleaseSource,
+    /// Channel the installer's self-update follows.
+    #[serde(rename = "releaseChannel")]
+    pub release_channel: ReleaseChannel,
+    /// Size limit of the download cache in MiB.
+    #[serde(rename = "downloadCacheLimitMb")]
+    pub download_cache_limit_mb: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            release_source: ReleaseSource::default(),
+            release_channel: ReleaseChannel::default(),
+            download_cache_limit_mb: DEFAULT_DOWNLOAD_CACHE_LIMIT_MB,
+        }
+    }
+}
+
+struct LoadedSettings {
+    path: PathBuf,
+    settings: Settings,
+}
+
+static SETTINGS: RwLock<Option<LoadedSettings>> = RwLock::new(None);
+
+/// Loads the settings file once at startup. A missing or unreadable file
+/// falls back to defaults so a bad edit never blocks the app from starting.
+pub(crate) fn init(app_handle: &AppHandle) -> InstallerResult<()> {
+    let config_dir = app_handle.path().app_config_dir().map_err(|e| {
+        InstallerError::invalid_data("Failed to resolve the app config directory").with_detail(e)
+    })?;
+    let path = config_dir.join(SETTINGS_FILE);
+
+    let settings = match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            eprintln!("Ignoring invalid settings file {:?}: {}", path, e);
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    };
+
+    *SETTINGS.write().unwrap() = Some(LoadedSettings { path, settings });
+    Ok(())
+}
+
+/// Current settings, or defaults when `init` has not run.
+pub(crate) fn current() -> Settings {
+    SETTINGS
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|loaded| loaded.settings.clone())
+        .unwrap_or_default()
+}
+
+/// Applies `change` and writes the result back to disk.
+pub(crate) fn update(change: impl FnOnce(&mut Settings)) -> InstallerResult<Settings> {
+    let mut guard = SETTINGS.write().unwrap();
+    let loaded = guard
+        .as_mut()
+        .ok_or_else(|| InstallerError::invalid_data("Settings have not been loaded"))?;
+
+    let mut settings = loaded.settings.clone();
+    change(&mut settings);
+
+    if let Some(parent) = loaded.path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| InstallerError::io("Failed to create config directory", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&settings)
+        .map_err(|e| InstallerError::invalid_data("Failed to serialize settings").with_detail(e))?;
+    fs::write(&loaded.path, contents)
+        .map_err(|e| InstallerError::io("Failed to write settings file", e))?;
+
+    loaded.settings = settings.clone();
+    Ok(settings)
+}
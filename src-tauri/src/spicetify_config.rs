@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::process_runner::hidden_command;
+use crate::spicetify_probe::SpicetifyProbe;
+
+const CONFIG_FILE: &str = "config-xpui.ini";
+
+/// Where the Spicetify CLI and `config-xpui.ini` live. Finding them runs
+/// the CLI, so commands resolve this once and pass it down.
+#[derive(Debug, Clone)]
+pub(crate) struct SpicetifyPaths {
+    /// `None` when only the config was found, e.g. after a partial uninstall.
+    pub binary: Option<PathBuf>,
+    pub config: PathBuf,
+}
+
+impl SpicetifyPaths {
+    pub(crate) fn resolve() -> InstallerResult<Self> {
+        Self::from_probe(&SpicetifyProbe::run())
+    }
+
+    /// Asks the probed CLI for its config first, since `spicetify -c`
+    /// honours `SPICETIFY_CONFIG`, then falls back to the default folders.
+    pub(crate) fn from_probe(probe: &SpicetifyProbe) -> InstallerResult<Self> {
+        let binary = probe.path.as_ref().map(PathBuf::from);
+        let config = binary
+            .as_deref()
+            .and_then(reported_config_path)
+            .or_else(|| default_locations().into_iter().find(|path| path.is_file()))
+            .ok_or_else(|| {
+                InstallerError::new(ErrorKind::SpicetifyNotFound, "Spicetify config not found")
+                    .with_remediation(
+                        "Install Spicetify, or run `spicetify` once to create its config.",
+                    )
+            })?;
+
+        Ok(Self { binary, config })
+    }
+
+    pub(crate) fn load_config(&self) -> InstallerResult<ConfigFile> {
+        ConfigFile::open(self.config.clone())
+    }
+
+    /// Folder holding the config and the user's `Themes`, `Extensions` and
+    /// `CustomApps` folders.
+    pub(crate) fn user_data_dir(&self) -> InstallerResult<PathBuf> {
+        self.config
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| InstallerError::invalid_data("Spicetify config has no parent folder"))
+    }
+
+    /// `folder` (e.g. `Themes`) in the user data folder, then the copy
+    /// bundled next to the CLI. Spicetify looks in the same order.
+    pub(crate) fn asset_dirs(&self, folder: &str) -> InstallerResult<Vec<PathBuf>> {
+        let mut dirs = vec![self.user_data_dir()?.join(folder)];
+        if let Some(install_dir) = self.binary.as_deref().and_then(Path::parent) {
+            let bundled = install_dir.join(folder);
+            if !dirs.contains(&bundled) {
+                dirs.push(bundled);
+            }
+        }
+        Ok(dirs)
+    }
+}
+
+fn reported_config_path(binary: &Path) -> Option<PathBuf> {
+    let output = hidden_command(binary).arg("-c").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let path = PathBuf::from(stdout.lines().next()?.trim());
+    path.is_file().then_some(path)
+}
+
+fn default_locations() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(dir) = env::var_os("SPICETIFY_CONFIG") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if cfg!(windows) {
+        if let Some(roaming) = env::var_os("APPDATA") {
+            dirs.push(PathBuf::from(roaming).join("spicetify"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("spicetify"));
+        }
+    } else {
+        if let Some(config_home) = env::var_os("XDG_CONFIG_HOME") {
+            dirs.push(PathBuf::from(config_home).join("spicetify"));
+        }
+        if let Some(home) = env::var_os("HOME") {
+            dirs.push(PathBuf::from(home).join(".config").join("spicetify"));
+        }
+    }
+
+    dirs.into_iter().map(|dir| dir.join(CONFIG_FILE)).collect()
+}
+
+/// One `key = value` line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSection {
+    pub name: String,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// The settings in `config-xpui.ini`, in file order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpicetifyConfig {
+    pub path: String,
+    pub sections: Vec<ConfigSection>,
+}
+
+/// `config-xpui.ini` kept as its original lines, so saving only touches the
+/// values that changed and keeps comments, ordering and line endings.
+pub(crate) struct ConfigFile {
+    path: PathBuf,
+    lines: Vec<String>,
+    line_ending: &'static str,
+}
+
+impl ConfigFile {
+    /// Resolves the paths for a single read or write of the config.
+    pub(crate) fn load() -> InstallerResult<Self> {
+        SpicetifyPaths::resolve()?.load_config()
+    }
+
+    pub(crate) fn open(path: PathBuf) -> InstallerResult<Self> {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| InstallerError::io("Failed to read Spicetify config", e))?;
+        Ok(Self::parse(path, &contents))
+    }
+
+    fn parse(path: PathBuf, contents: &str) -> Self {
+        let line_ending = if contents.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        Self {
+            path,
+            lines: contents.lines().map(str::to_string).collect(),
+            line_ending,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn get(&self, section: &str, key: &str) -> Option<String> {
+        let index = self.find_entry(section, key)?;
+        parse_entry(&self.lines[index]).map(|(_, value)| value.to_string())
+    }
+
+    /// A `|`-separated value such as `extensions`, without empty items.
+    pub(crate) fn get_list(&self, section: &str, key: &str) -> Vec<String> {
+        self.get(section, key)
+            .unwrap_or_default()
+            .split('|')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Adds `item` to or drops it from a `|`-separated value. Returns
+    /// whether the value changed.
+    pub(crate) fn set_list_item(
+        &mut self,
+        section: &str,
+        key: &str,
+        item: &str,
+        listed: bool,
+    ) -> InstallerResult<bool> {
+        let mut items = self.get_list(section, key);
+        if items.iter().any(|known| known == item) == listed {
+            return Ok(false);
+        }
+
+        if listed {
+            items.push(item.to_string());
+        } else {
+            items.retain(|known| known != item);
+        }
+        self.set(section, key, &items.join("|"))?;
+        Ok(true)
+    }
+
+    /// Replaces the value of `key`, keeping the spacing around `=`. A missing
+    /// key is added at the end of its section, and a missing section at the
+    /// end of the file.
+    pub(crate) fn set(&mut self, section: &str, key: &str, value: &str) -> InstallerResult<()> {
+        validate_token("section", section)?;
+        validate_token("key", key)?;
+        if value.contains(['\r', '\n']) {
+            return Err(InstallerError::invalid_data(
+                "Config values cannot span multiple lines",
+            ));
+        }
+
+        if let Some(index) = self.find_entry(section, key) {
+            let line = &self.lines[index];
+            let equals = line.find('=').unwrap_or(line.len());
+            let after = &line[equals + 1..];
+            let padding = &after[..after.len() - after.trim_start().len()];
+            // `key =` has nothing after `=` to copy, so space it like the key
+            let padding = if padding.is_empty() && line[..equals].ends_with(' ') {
+                " "
+            } else {
+                padding
+            };
+            self.lines[index] = format!("{}={}{}", &line[..equals], padding, value);
+            return Ok(());
+        }
+
+        let entry = format!("{} = {}", key, value);
+        match self.section_range(section) {
+            Some((header, end)) => {
+                // After the last entry, before any blank lines separating sections
+                let mut insert_at = end;
+                while insert_at > header + 1 && self.lines[insert_at - 1].trim().is_empty() {
+                    insert_at -= 1;
+                }
+                self.lines.insert(insert_at, entry);
+            }
+            None => {
+                if self
+                    .lines
+                    .last()
+                    .is_some_and(|line| !line.trim().is_empty())
+                {
+                    self.lines.push(String::new());
+                }
+                self.lines.push(format!("[{}]", section));
+                self.lines.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn save(&self) -> InstallerResult<()> {
+        fs::write(&self.path, self.contents())
+            .map_err(|e| InstallerError::io("Failed to write Spicetify config", e))
+    }
+
+    fn contents(&self) -> String {
+        let mut contents = self.lines.join(self.line_ending);
+        contents.push_str(self.line_ending);
+        contents
+    }
+
+    pub(crate) fn to_view(&self) -> SpicetifyConfig {
+        let mut sections: Vec<ConfigSection> = Vec::new();
+        for line in &self.lines {
+            if let Some(name) = parse_section(line) {
+                sections.push(ConfigSection {
+                    name: name.to_string(),
+                    entries: Vec::new(),
+                });
+            } else if let (Some(section), Some((key, value))) =
+                (sections.last_mut(), parse_entry(line))
+            {
+                section.entries.push(ConfigEntry {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                });
+            }
+        }
+
+        SpicetifyConfig {
+            path: self.path.to_string_lossy().to_string(),
+            sections,
+        }
+    }
+
+    /// Index of the section header and the index one past its last line.
+    fn section_range(&self, section: &str) -> Option<(usize, usize)> {
+        let header = self.lines.iter().position(|line| {
+            parse_section(line).is_some_and(|name| name.eq_ignore_ascii_case(section))
+        })?;
+        let end = self.lines[header + 1..]
+            .iter()
+            .position(|line| parse_section(line).is_some())
+            .map_or(self.lines.len(), |offset| header + 1 + offset);
+        Some((header, end))
+    }
+
+    fn find_entry(&self, section: &str, key: &str) -> Option<usize> {
+        let (header, end) = self.section_range(section)?;
+        (header + 1..end)
+            .find(|&index| parse_entry(&self.lines[index]).is_some_and(|(name, _)| name == key))
+    }
+}
+
+fn parse_section(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+
+/// Splits `key = value`. Comments (`;` or `#`) and blank lines are `None`.
+fn parse_entry(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+        return None;
+    }
+    let (key, value) = trimmed.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+fn validate_token(label: &str, text: &str) -> InstallerResult<()> {
+    if text.trim().is_empty() || text.contains(['=', '[', ']', '\r', '\n']) {
+        return Err(InstallerError::invalid_data(format!(
+            "Invalid config {}: {:?}",
+            label, text
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> ConfigFile {
+        ConfigFile::parse(PathBuf::from(CONFIG_FILE), contents)
+    }
+
+    #[test]
+    fn set() {
+        // (before, section, key, value, after)
+        let cases = [
+            (
+                "[Setting]\ncurrent_theme = SpicetifyDefault\n",
+                "Setting",
+                "current_theme",
+                "Sleek",
+                "[Setting]\ncurrent_theme = Sleek\n",
+            ),
+            (
+                "[Setting]\ncurrent_theme=SpicetifyDefault\n",
+                "Setting",
+                "current_theme",
+                "Sleek",
+                "[Setting]\ncurrent_theme=Sleek\n",
+            ),
+            (
+                "[Setting]\ncolor_scheme =\n",
+                "Setting",
+                "color_scheme",
+                "Nord",
+                "[Setting]\ncolor_scheme = Nord\n",
+            ),
+            (
+                "[Setting]\ncolor_scheme     = \n",
+                "Setting",
+                "color_scheme",
+                "Nord",
+                "[Setting]\ncolor_scheme     = Nord\n",
+            ),
+            (
+                "[Setting]\ninject_css = 1\n\n[Preprocesses]\nexpose_apis = 1\n",
+                "Setting",
+                "replace_colors",
+                "1",
+                "[Setting]\ninject_css = 1\nreplace_colors = 1\n\n[Preprocesses]\nexpose_apis = 1\n",
+            ),
+            (
+                "[Setting]\ninject_css = 1\n",
+                "AdditionalOptions",
+                "extensions",
+                "shuffle+.js",
+                "[Setting]\ninject_css = 1\n\n[AdditionalOptions]\nextensions = shuffle+.js\n",
+            ),
+            (
+                "; Spicetify config\r\n[Setting]\r\n# theme\r\ncurrent_theme = A\r\n",
+                "Setting",
+                "current_theme",
+                "B",
+                "; Spicetify config\r\n[Setting]\r\n# theme\r\ncurrent_theme = B\r\n",
+            ),
+            (
+                "[Setting]\r\ninject_css = 1\r\n",
+                "Backup",
+                "version",
+                "1.2.3",
+                "[Setting]\r\ninject_css = 1\r\n\r\n[Backup]\r\nversion = 1.2.3\r\n",
+            ),
+        ];
+
+        for (before, section, key, value, after) in cases {
+            let mut config = parse(before);
+            config.set(section, key, value).unwrap();
+            assert_eq!(config.contents(), after, "[{}] {} = {}", section, key, value);
+        }
+    }
+
+    #[test]
+    fn set_rejects_invalid_input() {
+        let cases = [
+            ("", "key", "value"),
+            ("Setting", "", "value"),
+            ("Set]ting", "key", "value"),
+            ("Setting", "a=b", "value"),
+            ("Setting", "key", "two\nlines"),
+        ];
+
+        for (section, key, value) in cases {
+            let mut config = parse("[Setting]\n");
+            assert!(
+                config.set(section, key, value).is_err(),
+                "[{:?}] {:?} = {:?}",
+                section,
+                key,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn list_items() {
+        // (before, item, listed, changed, after)
+        let cases = [
+            ("a.js|b.js", "c.js", true, true, "a.js|b.js|c.js"),
+            ("a.js|b.js", "a.js", true, false, "a.js|b.js"),
+            ("a.js|b.js", "a.js", false, true, "b.js"),
+            ("a.js|b.js", "c.js", false, false, "a.js|b.js"),
+            ("", "a.js", true, true, "a.js"),
+            ("a.js||b.js|", "b.js", false, true, "a.js"),
+        ];
+
+        for (before, item, listed, changed, after) in cases {
+            let mut config = parse(&format!("[AdditionalOptions]\nextensions = {}\n", before));
+            assert_eq!(
+                config
+                    .set_list_item("AdditionalOptions", "extensions", item, listed)
+                    .unwrap(),
+                changed,
+                "{:?} {} {}",
+                before,
+                if listed { "+" } else { "-" },
+                item
+            );
+            assert_eq!(
+                config.get("AdditionalOptions", "extensions").as_deref(),
+                Some(after),
+                "{:?} {} {}",
+                before,
+                if listed { "+" } else { "-" },
+                item
+            );
+        }
+    }
+}
@@ -0,0 +1,644 @@
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::archive;
+use crate::download_cache::{self, CacheKey, CachedProduct};
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::process_runner::{check_cancelled, wait_for_cancellation, OutputLine};
+use crate::release_source::{fetch_json, ReleaseSource};
+use crate::spicetify_probe::{binary_name, read_version, SpicetifyProbe};
+use crate::version::{is_newer, Version};
+
+/// Bundled folder that every Spicetify release ships next to the binary.
+const INSTALL_DIR_MARKER: &str = "jsHelper";
+
+/// Latest published Spicetify version, without a leading `v`.
+pub(crate) async fn fetch_latest_spicetify_version() -> InstallerResult<String> {
+    let release_info = ReleaseSource::current()
+        .spicetify
+        .fetch_latest_release("Spicetify-Installer")
+        .await?;
+
+    release_info["tag_name"]
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| InstallerError::invalid_data("Missing tag_name in release response"))
+}
+
+/// A published Spicetify release the user can pin.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpicetifyRelease {
+    pub version: String,
+    pub prerelease: bool,
+    #[serde(rename = "publishedAt")]
+    pub published_at: Option<String>,
+}
+
+/// Published Spicetify releases, newest first. Drafts and tags that are not
+/// versions are skipped.
+pub(crate) async fn list_spicetify_releases() -> InstallerResult<Vec<SpicetifyRelease>> {
+    let url = format!(
+        "{}?per_page=100",
+        ReleaseSource::current().spicetify.releases_url()
+    );
+    let releases = fetch_json(&url, "Spicetify-Installer").await?;
+    let releases = releases
+        .as_array()
+        .ok_or_else(|| InstallerError::invalid_data("Release list is not an array"))?;
+
+    let mut versions: Vec<(Version, SpicetifyRelease)> = releases
+        .iter()
+        .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+        .filter_map(|release| {
+            let version = Version::parse(release["tag_name"].as_str()?)?;
+            let entry = SpicetifyRelease {
+                version: version.to_string(),
+                prerelease: release["prerelease"].as_bool().unwrap_or(false),
+                published_at: release["published_at"].as_str().map(str::to_string),
+            };
+            Some((version, entry))
+        })
+        .collect();
+
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(versions.into_iter().map(|(_, release)| release).collect())
+}
+
+/// Resolves the Spicetify version to install: the pinned `requested` version
+/// when given, otherwise the latest release. Shared by the Windows script
+/// runner and the native Linux pipeline so both install the same release.
+pub(crate) async fn resolve_target_version(requested: Option<&str>) -> InstallerResult<String> {
+    if let Some(requested) = requested {
+        let version = Version::parse(requested).ok_or_else(|| {
+            InstallerError::invalid_data(format!("Invalid Spicetify version: {}", requested))
+        })?;
+        return Ok(version.to_string());
+    }
+
+    let version = fetch_latest_spicetify_version().await.map_err(|e| {
+        // Installing from the cache stays an explicit choice of version
+        let remediation = match newest_cached_version() {
+            Some(cached) => format!(
+                "The latest version could not be determined. Choose Spicetify v{} to install it from the download cache, or pick another version.",
+                cached
+            ),
+            None => "The latest version could not be determined. Choose a specific Spicetify version to install instead.".to_string(),
+        };
+        e.with_remediation(remediation)
+    })?;
+    Ok(version)
+}
+
+/// Newest Spicetify version in the download cache, offered when the latest
+/// release cannot be resolved.
+fn newest_cached_version() -> Option<String> {
+    download_cache::entries(CachedProduct::Spicetify)
+        .into_iter()
+        .filter_map(|entry| Version::parse(&entry.version))
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// Name of the release asset for the current platform, e.g.
+/// `spicetify-2.36.0-linux-amd64.tar.gz`.
+pub(crate) fn release_asset_name(version: &str) -> InstallerResult<String> {
+    let arch = env::consts::ARCH;
+
+    let (os, arch, extension) = match env::consts::OS {
+        "windows" => {
+            let arch = match arch {
+                "x86_64" => "x64",
+                "aarch64" => "arm64",
+                _ => "x32",
+            };
+            ("windows", arch, "zip")
+        }
+        "linux" => {
+            let arch = match arch {
+                "x86_64" => "amd64",
+                "aarch64" => "arm64",
+                other => {
+                    return Err(InstallerError::unsupported(format!(
+                        "Unsupported Linux architecture: {}",
+                        other
+                    )))
+                }
+            };
+            ("linux", arch, "tar.gz")
+        }
+        "macos" => {
+            let arch = match arch {
+                "x86_64" => "amd64",
+                "aarch64" => "arm64",
+                other => {
+                    return Err(InstallerError::unsupported(format!(
+                        "Unsupported macOS architecture: {}",
+                        other
+                    )))
+                }
+            };
+            ("darwin", arch, "tar.gz")
+        }
+        other => {
+            return Err(InstallerError::unsupported(format!(
+                "Unsupported operating system: {}",
+                other
+            )))
+        }
+    };
+
+    Ok(format!("spicetify-{}-{}-{}.{}", version, os, arch, extension))
+}
+
+fn release_download_url(version: &str) -> InstallerResult<String> {
+    Ok(ReleaseSource::current()
+        .spicetify
+        .download_url(&format!("v{}", version), &release_asset_name(version)?))
+}
+
+/// A release archive ready to extract.
+pub(crate) struct ReleaseArchive {
+    pub path: PathBuf,
+    /// Whether it came from the download cache instead of the network.
+    pub cached: bool,
+}
+
+/// The release archive for `version`, from the download cache when present.
+/// A fresh download is moved into the cache, so the returned file must not
+/// be deleted.
+pub(crate) async fn fetch_release_archive(version: &str) -> InstallerResult<ReleaseArchive> {
+    let asset_name = release_asset_name(version)?;
+    let key = CacheKey::new(CachedProduct::Spicetify, version, &asset_name);
+
+    if let Some(path) = download_cache::lookup(&key) {
+        return Ok(ReleaseArchive { path, cached: true });
+    }
+
+    let download_url = release_download_url(version)?;
+    let download_path = env::temp_dir().join(&asset_name);
+
+    check_cancelled()?;
+    tokio::select! {
+        result = download_archive(&download_url, &download_path) => result?,
+        _ = wait_for_cancellation() => {
+            let _ = fs::remove_file(&download_path);
+            return Err(InstallerError::cancelled());
+        }
+    }
+
+    let path = download_cache::store(&key, &download_path).inspect_err(|_| {
+        let _ = fs::remove_file(&download_path);
+    })?;
+    Ok(ReleaseArchive {
+        path,
+        cached: false,
+    })
+}
+
+fn home_dir() -> InstallerResult<PathBuf> {
+    env::var("HOME")
+        .map(PathBuf::from)
+        .map_err(|_| InstallerError::unsupported("HOME environment variable is not set"))
+}
+
+fn linux_install_dir() -> InstallerResult<PathBuf> {
+    Ok(home_dir()?.join(".spicetify"))
+}
+
+/// Streams progress and output lines of a native operation to the
+/// frontend and keeps them as the operation's result.
+pub(crate) struct InstallLog {
+    app_handle: AppHandle,
+    pub output: String,
+}
+
+impl InstallLog {
+    pub(crate) fn new(app_handle: &AppHandle) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            output: String::new(),
+        }
+    }
+
+    pub(crate) fn step(&mut self, progress: u32, message: &str) {
+        self.line(message);
+        let _ = self.app_handle.emit("progress_update", progress);
+    }
+
+    pub(crate) fn line(&mut self, message: &str) {
+        self.output.push_str(message);
+        self.output.push('\n');
+        let _ = self.app_handle.emit(
+            "operation_output",
+            OutputLine {
+                stream: "stdout",
+                line: message.to_string(),
+            },
+        );
+    }
+}
+
+/// Native Linux counterpart of the embedded PowerShell installer: downloads
+/// the release tarball, extracts it into `~/.spicetify` and verifies it runs.
+pub(crate) async fn install_spicetify_linux(
+    app_handle: AppHandle,
+    requested_version: Option<String>,
+) -> InstallerResult<String> {
+    let mut log = InstallLog::new(&app_handle);
+
+    log.step(0, "Starting Spicetify installation process...");
+
+    log.step(5, "Resolving the Spicetify version...");
+    let version = resolve_target_version(requested_version.as_deref()).await?;
+    log.line(&format!(" > Installing version {}", version));
+
+    let install_dir = linux_install_dir()?;
+
+    log.step(15, &format!("Downloading Spicetify v{}...", version));
+    let archive = fetch_release_archive(&version).await?;
+    log.line(if archive.cached { " > Using cached archive" } else { " > OK" });
+
+    finish_linux_install(&mut log, &archive.path, &install_dir)?;
+
+    Ok(log.output)
+}
+
+/// Installs Spicetify from a local release archive without network access.
+pub(crate) async fn install_spicetify_linux_from_archive(
+    app_handle: AppHandle,
+    archive_path: PathBuf,
+) -> InstallerResult<String> {
+    let mut log = InstallLog::new(&app_handle);
+
+    log.step(0, "Starting offline Spicetify installation...");
+
+    log.step(5, &format!("Inspecting {}...", archive_path.display()));
+    let version = inspect_local_archive(&archive_path)?;
+    log.line(&format!(" > Installing version {}", version));
+
+    let install_dir = linux_install_dir()?;
+    finish_linux_install(&mut log, &archive_path, &install_dir)?;
+
+    Ok(log.output)
+}
+
+/// Extract, PATH and verify steps shared by the online and offline installs.
+fn finish_linux_install(
+    log: &mut InstallLog,
+    archive_path: &Path,
+    install_dir: &Path,
+) -> InstallerResult<()> {
+    check_cancelled()?;
+
+    log.step(50, &format!("Extracting Spicetify to {}...", install_dir.display()));
+    archive::extract(archive_path, install_dir)?;
+    log.line(" > OK");
+
+    let binary_path = install_dir.join(binary_name());
+    log.step(70, "Marking Spicetify as executable...");
+    if !binary_path.exists() {
+        return Err(InstallerError::invalid_data(format!(
+            "Spicetify executable not found after extraction at {}",
+            binary_path.display()
+        )));
+    }
+    mark_executable(&binary_path)?;
+    log.line(" > OK");
+
+    check_cancelled()?;
+    log.step(80, "Making Spicetify available in the PATH...");
+    for message in add_to_shell_path(install_dir)? {
+        log.line(&format!(" > {}", message));
+    }
+
+    verify_installation(log, &binary_path)?;
+
+    log.step(100, "Spicetify was successfully installed!");
+    log.line(&format!("Installation directory: {}", install_dir.display()));
+    log.line("Open a new terminal window if the spicetify command is not recognized.");
+
+    Ok(())
+}
+
+/// Runs the installed binary with `-v` to confirm it works.
+fn verify_installation(log: &mut InstallLog, binary_path: &Path) -> InstallerResult<()> {
+    log.step(90, "Verifying Spicetify installation...");
+    let output = std::process::Command::new(binary_path)
+        .arg("-v")
+        .output()
+        .map_err(|e| InstallerError::io("Failed to run Spicetify", e))?;
+
+    if !output.status.success() {
+        return Err(InstallerError::new(
+            ErrorKind::ProcessFailed,
+            "Spicetify verification failed",
+        )
+        .with_detail(String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let installed_version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    log.line(&format!(" > Version: {}", installed_version));
+    Ok(())
+}
+
+/// Checks that `archive_path` is a Spicetify release for this platform and
+/// returns its version, taken from the file name when it follows the release
+/// naming and otherwise from running the bundled binary.
+pub(crate) fn inspect_local_archive(archive_path: &Path) -> InstallerResult<String> {
+    if !archive_path.is_file() {
+        return Err(InstallerError::invalid_data(format!(
+            "Archive not found: {}",
+            archive_path.display()
+        )));
+    }
+
+    let file_name = archive_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // The Windows script extracts with Expand-Archive, which only reads zip.
+    if cfg!(windows) && !file_name.to_lowercase().ends_with(".zip") {
+        return Err(InstallerError::unsupported(format!(
+            "{} is not a zip archive",
+            file_name
+        ))
+        .with_remediation("Use the Windows .zip release of Spicetify."));
+    }
+
+    let entries = archive::entry_names(archive_path)?;
+    let binary = binary_name();
+    if !entries.iter().any(|entry| entry.trim_start_matches("./") == binary) {
+        return Err(InstallerError::invalid_data(format!(
+            "{} does not contain {}",
+            archive_path.display(),
+            binary
+        ))
+        .with_remediation("Use an official spicetify-<version>-<os>-<arch> release archive."));
+    }
+
+    if let Some(version) = version_from_asset_name(&file_name) {
+        let expected = release_asset_name(&version)?;
+        if !file_name.eq_ignore_ascii_case(&expected) {
+            return Err(InstallerError::unsupported(format!(
+                "{} is not built for this system",
+                file_name
+            ))
+            .with_remediation(format!("Use {} instead.", expected)));
+        }
+        return Ok(version);
+    }
+
+    let staging_dir = env::temp_dir().join("spicetify_inspect");
+    let _ = fs::remove_dir_all(&staging_dir);
+    let version = archive::extract(archive_path, &staging_dir).and_then(|_| {
+        let binary_path = staging_dir.join(&binary);
+        mark_executable(&binary_path)?;
+        read_version(&binary_path).map_err(|e| {
+            InstallerError::new(
+                ErrorKind::ProcessFailed,
+                "The Spicetify binary in the archive does not run on this system",
+            )
+            .with_detail(e)
+        })
+    });
+    let _ = fs::remove_dir_all(&staging_dir);
+    version
+}
+
+/// `2.36.0` from `spicetify-2.36.0-linux-amd64.tar.gz`.
+fn version_from_asset_name(file_name: &str) -> Option<String> {
+    let lowered = file_name.to_lowercase();
+    let stem = lowered
+        .strip_suffix(".zip")
+        .or_else(|| lowered.strip_suffix(".tar.gz"))?;
+    let rest = stem.strip_prefix("spicetify-")?;
+
+    let mut parts: Vec<&str> = rest.rsplitn(3, '-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let version = parts.pop()?;
+    Version::parse(version).map(|version| version.to_string())
+}
+
+/// Replaces the installed Spicetify binary with the latest release, in the
+/// folder the probe found it in. Bundled files next to it are refreshed too
+/// when that folder is a Spicetify install folder.
+pub(crate) async fn upgrade_spicetify(app_handle: AppHandle) -> InstallerResult<String> {
+    let mut log = InstallLog::new(&app_handle);
+
+    log.step(0, "Starting Spicetify upgrade...");
+
+    log.step(5, "Locating the installed Spicetify...");
+    let probe = SpicetifyProbe::run();
+    let (Some(binary_path), Some(current_version)) = (probe.path, probe.version) else {
+        return Err(
+            InstallerError::new(ErrorKind::SpicetifyNotFound, "Spicetify is not installed")
+                .with_remediation("Install Spicetify first."),
+        );
+    };
+    let binary_path = PathBuf::from(binary_path);
+    let install_dir = binary_path
+        .parent()
+        .ok_or_else(|| InstallerError::invalid_data("Spicetify binary has no parent folder"))?
+        .to_path_buf();
+    log.line(&format!(" > Found v{} at {}", current_version, binary_path.display()));
+
+    log.step(10, "Fetching the latest Spicetify version...");
+    let version = fetch_latest_spicetify_version().await?;
+    if !is_newer(&version, &current_version) {
+        log.step(100, &format!("Spicetify v{} is already up to date.", current_version));
+        return Ok(log.output);
+    }
+    log.line(&format!(" > Upgrading v{} to v{}", current_version, version));
+
+    let staging_dir = env::temp_dir().join("spicetify_upgrade");
+
+    log.step(20, &format!("Downloading Spicetify v{}...", version));
+    let release_archive = fetch_release_archive(&version).await?;
+    log.line(if release_archive.cached { " > Using cached archive" } else { " > OK" });
+
+    log.step(60, "Extracting the new release...");
+    let _ = fs::remove_dir_all(&staging_dir);
+    archive::extract(&release_archive.path, &staging_dir)?;
+    log.line(" > OK");
+
+    if let Err(e) = check_cancelled() {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+
+    log.step(75, &format!("Replacing {}...", binary_path.display()));
+    let replaced = replace_installation(&staging_dir, &install_dir, &binary_path);
+    let _ = fs::remove_dir_all(&staging_dir);
+    let refreshed_bundle = replaced?;
+    mark_executable(&binary_path)?;
+    log.line(" > OK");
+    if !refreshed_bundle {
+        log.line(&format!(
+            " > {} is not a Spicetify folder, so only the binary was replaced",
+            install_dir.display()
+        ));
+    }
+
+    verify_installation(&mut log, &binary_path)?;
+
+    log.step(100, &format!("Spicetify was upgraded to v{}!", version));
+    log.line("Run Apply to re-apply your customizations to Spotify.");
+
+    Ok(log.output)
+}
+
+/// Copies the extracted release over `install_dir`. The binary is moved
+/// aside first and put back if the copy fails, so a failed upgrade never
+/// leaves the user without a working `spicetify`. The bundled files are only
+/// copied into a folder that already holds them, never into a shared one
+/// like `~/.local/bin`. Returns whether they were copied.
+fn replace_installation(
+    staging_dir: &Path,
+    install_dir: &Path,
+    binary_path: &Path,
+) -> InstallerResult<bool> {
+    let staged_binary = staging_dir.join(binary_name());
+    if !staged_binary.is_file() {
+        return Err(InstallerError::invalid_data(format!(
+            "Release archive does not contain {}",
+            binary_name()
+        )));
+    }
+
+    let backup_path = PathBuf::from(format!("{}.old", binary_path.display()));
+    let _ = fs::remove_file(&backup_path);
+    fs::rename(binary_path, &backup_path)
+        .map_err(|e| InstallerError::io("Failed to move the old Spicetify binary aside", e))?;
+
+    if let Err(e) = fs::copy(&staged_binary, binary_path) {
+        let _ = fs::rename(&backup_path, binary_path);
+        return Err(InstallerError::io("Failed to install the new Spicetify binary", e));
+    }
+    let _ = fs::remove_file(&backup_path);
+
+    if !install_dir.join(INSTALL_DIR_MARKER).is_dir() {
+        return Ok(false);
+    }
+    copy_dir_contents(staging_dir, install_dir, Some(&staged_binary))?;
+    Ok(true)
+}
+
+/// Copies everything in `source` into `destination`, except `skip`.
+pub(crate) fn copy_dir_contents(
+    source: &Path,
+    destination: &Path,
+    skip: Option<&Path>,
+) -> InstallerResult<()> {
+    fs::create_dir_all(destination)
+        .map_err(|e| InstallerError::io("Failed to create directory", e))?;
+
+    let entries =
+        fs::read_dir(source).map_err(|e| InstallerError::io("Failed to read directory", e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| InstallerError::io("Failed to read directory", e))?;
+        let path = entry.path();
+        if skip == Some(path.as_path()) {
+            continue;
+        }
+
+        let target = destination.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir_contents(&path, &target, skip)?;
+        } else {
+            fs::copy(&path, &target).map_err(|e| {
+                InstallerError::io(format!("Failed to copy {}", target.display()), e)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn download_archive(url: &str, destination: &Path) -> InstallerResult<()> {
+    let client = reqwest::Client::builder()
+        .user_agent("Spicetify-Installer")
+        .build()
+        .map_err(|e| InstallerError::network("Failed to create HTTP client", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| InstallerError::network(format!("Failed to download {}", url), e))?;
+
+    if !response.status().is_success() {
+        return Err(InstallerError::http_status(
+            format!("Download of {} failed", url),
+            &response,
+        ));
+    }
+
+    let content = response
+        .bytes()
+        .await
+        .map_err(|e| InstallerError::network("Failed to read download content", e))?;
+
+    fs::write(destination, content).map_err(|e| InstallerError::io("Failed to write archive", e))
+}
+
+#[cfg(unix)]
+fn mark_executable(path: &Path) -> InstallerResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| InstallerError::io("Failed to read permissions", e))?
+        .permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)
+        .map_err(|e| InstallerError::io("Failed to set permissions", e))
+}
+
+#[cfg(not(unix))]
+fn mark_executable(_path: &Path) -> InstallerResult<()> {
+    Ok(())
+}
+
+/// Appends the install directory to the PATH in the user's shell profiles,
+/// mirroring `Add-SpicetifyToPath` in the Windows script.
+fn add_to_shell_path(install_dir: &Path) -> InstallerResult<Vec<String>> {
+    let home = home_dir()?;
+    let export_line = format!("export PATH=$PATH:{}", install_dir.display());
+
+    let mut profiles: Vec<PathBuf> = [".bashrc", ".zshrc"]
+        .iter()
+        .map(|name| Path::new(&home).join(name))
+        .filter(|path| path.exists())
+        .collect();
+    if profiles.is_empty() {
+        profiles.push(Path::new(&home).join(".profile"));
+    }
+
+    let mut messages = Vec::new();
+    for profile in profiles {
+        let contents = fs::read_to_string(&profile).unwrap_or_default();
+        if contents.contains(&install_dir.display().to_string()) {
+            messages.push(format!("{} already contains Spicetify", profile.display()));
+            continue;
+        }
+
+        let mut updated = contents;
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&export_line);
+        updated.push('\n');
+
+        fs::write(&profile, updated).map_err(|e| {
+            InstallerError::io(format!("Failed to update {}", profile.display()), e)
+        })?;
+        messages.push(format!("Path added to {}", profile.display()));
+    }
+
+    Ok(messages)
+}
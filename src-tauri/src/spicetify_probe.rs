@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::process_runner::hidden_command;
+
+/// How the Spicetify binary was located.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProbeMethod {
+    /// One of the folders the official installers use.
+    KnownDirectory,
+    /// A directory listed in `PATH`.
+    PathSearch,
+}
+
+/// One candidate location that was checked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeAttempt {
+    pub method: ProbeMethod,
+    pub path: String,
+    /// Why this candidate was rejected; `None` for the one that worked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of looking for an installed Spicetify CLI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpicetifyProbe {
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub method: Option<ProbeMethod>,
+    pub attempts: Vec<ProbeAttempt>,
+}
+
+impl SpicetifyProbe {
+    /// Checks the known install folders, then `PATH`, and runs the first
+    /// binary found with `-v`. A candidate that fails to run is recorded and
+    /// the search moves on to the next one.
+    pub fn run() -> Self {
+        let mut probe = Self {
+            path: None,
+            version: None,
+            method: None,
+            attempts: Vec::new(),
+        };
+
+        let candidates = known_locations()
+            .into_iter()
+            .map(|path| (ProbeMethod::KnownDirectory, path))
+            .chain(
+                path_locations()
+                    .into_iter()
+                    .map(|path| (ProbeMethod::PathSearch, path)),
+            );
+
+        let mut checked: Vec<PathBuf> = Vec::new();
+        for (method, path) in candidates {
+            if checked.contains(&path) {
+                continue;
+            }
+            checked.push(path.clone());
+
+            let display = path.to_string_lossy().to_string();
+            match read_version(&path) {
+                Ok(version) => {
+                    probe.attempts.push(ProbeAttempt {
+                        method,
+                        path: display.clone(),
+                        error: None,
+                    });
+                    probe.path = Some(display);
+                    probe.version = Some(version);
+                    probe.method = Some(method);
+                    break;
+                }
+                Err(error) => probe.attempts.push(ProbeAttempt {
+                    method,
+                    path: display,
+                    error: Some(error),
+                }),
+            }
+        }
+
+        let searched_path = probe
+            .attempts
+            .iter()
+            .any(|attempt| attempt.method == ProbeMethod::PathSearch);
+        if probe.path.is_none() && !searched_path {
+            probe.attempts.push(ProbeAttempt {
+                method: ProbeMethod::PathSearch,
+                path: "PATH".to_string(),
+                error: Some(format!("No {} found in PATH", binary_name())),
+            });
+        }
+
+        probe
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+pub(crate) fn binary_name() -> String {
+    format!("spicetify{}", env::consts::EXE_SUFFIX)
+}
+
+/// Install folders used by the official scripts and by older releases.
+fn known_locations() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if cfg!(windows) {
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("spicetify"));
+        }
+        if let Some(profile) = env::var_os("USERPROFILE") {
+            dirs.push(PathBuf::from(profile).join("spicetify-cli"));
+        }
+        if let Some(roaming) = env::var_os("APPDATA") {
+            dirs.push(PathBuf::from(roaming).join("spicetify"));
+        }
+    } else if let Some(home) = env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        dirs.push(home.join(".spicetify"));
+        dirs.push(home.join("spicetify-cli"));
+        dirs.push(home.join(".local").join("bin"));
+    }
+
+    let name = binary_name();
+    dirs.into_iter().map(|dir| dir.join(&name)).collect()
+}
+
+/// Every `spicetify` binary reachable through `PATH`, in lookup order.
+fn path_locations() -> Vec<PathBuf> {
+    let Some(path) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+
+    let name = binary_name();
+    env::split_paths(&path)
+        .map(|dir| dir.join(&name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Runs `<path> -v` and returns the printed version.
+pub(crate) fn read_version(path: &Path) -> Result<String, String> {
+    if !path.is_file() {
+        return Err("Not found".to_string());
+    }
+
+    let output = hidden_command(path)
+        .arg("-v")
+        .output()
+        .map_err(|e| format!("Failed to run: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("Exited with {}", output.status)
+        } else {
+            format!("Exited with {}: {}", output.status, stderr)
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout
+        .lines()
+        .next()
+        .unwrap_or("")
+        .trim()
+        .trim_start_matches('v')
+        .to_string();
+
+    if version.is_empty() {
+        return Err("Printed no version".to_string());
+    }
+    Ok(version)
+}
@@ -0,0 +1,47 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Version of the Spotify client last launched on this machine, read from
+/// its `prefs` file. `None` when Spotify has never run.
+pub(crate) fn installed_version() -> Option<String> {
+    prefs_locations().into_iter().find_map(|path| {
+        let prefs = fs::read_to_string(path).ok()?;
+        prefs.lines().find_map(|line| {
+            let value = line.strip_prefix("app.last-launched-version=")?;
+            let value = value.trim().trim_matches('"');
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    })
+}
+
+/// `prefs` files of the desktop, Microsoft Store and Flatpak builds.
+fn prefs_locations() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if cfg!(windows) {
+        if let Some(roaming) = env::var_os("APPDATA") {
+            paths.push(PathBuf::from(roaming).join("Spotify").join("prefs"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            paths.push(
+                PathBuf::from(local)
+                    .join("Packages")
+                    .join("SpotifyAB.SpotifyMusic_zpdnekdrzrea0")
+                    .join("LocalState")
+                    .join("Spotify")
+                    .join("prefs"),
+            );
+        }
+    } else if let Some(home) = env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        if cfg!(target_os = "macos") {
+            paths.push(home.join("Library/Application Support/Spotify/prefs"));
+        } else {
+            paths.push(home.join(".config/spotify/prefs"));
+            paths.push(home.join(".var/app/com.spotify.Client/config/spotify/prefs"));
+        }
+    }
+
+    paths
+}
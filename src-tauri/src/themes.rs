@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{InstallerError, InstallerResult};
+use crate::spicetify_config::{ConfigFile, SpicetifyPaths};
+
+const THEMES_DIR: &str = "Themes";
+const COLOR_FILE: &str = "color.ini";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeColor {
+    pub name: String,
+    /// Hex without `#`, as written in `color.ini`.
+    pub value: String,
+}
+
+/// A `[section]` of a theme's `color.ini`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorScheme {
+    pub name: String,
+    pub colors: Vec<ThemeColor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Theme {
+    pub name: String,
+    pub path: String,
+    /// Empty for themes without a `color.ini`.
+    pub color_schemes: Vec<ColorScheme>,
+    pub has_user_css: bool,
+}
+
+/// Installed themes and the ones selected in `config-xpui.ini`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemesOverview {
+    pub current_theme: Option<String>,
+    pub color_scheme: Option<String>,
+    pub themes: Vec<Theme>,
+}
+
+/// Themes from the user's `Themes` folder, then ones bundled with the CLI.
+/// A user theme hides a bundled one of the same name, as in Spicetify.
+pub(crate) fn list(paths: &SpicetifyPaths) -> InstallerResult<ThemesOverview> {
+    let config = paths.load_config()?;
+
+    let mut themes: Vec<Theme> = Vec::new();
+    for dir in paths.asset_dirs(THEMES_DIR)? {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut found: Vec<Theme> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .filter_map(|path| read_theme(&path))
+            .filter(|theme| !themes.iter().any(|known| known.name == theme.name))
+            .collect();
+        found.sort_by_key(|theme| theme.name.to_lowercase());
+        themes.extend(found);
+    }
+
+    Ok(ThemesOverview {
+        current_theme: config
+            .get("Setting", "current_theme")
+            .filter(|value| !value.is_empty()),
+        color_scheme: config
+            .get("Setting", "color_scheme")
+            .filter(|value| !value.is_empty()),
+        themes,
+    })
+}
+
+/// Selects `theme` in `config-xpui.ini`. Without a `color_scheme`, the
+/// theme's first scheme is used.
+pub(crate) fn select(
+    paths: &SpicetifyPaths,
+    theme: &str,
+    color_scheme: Option<&str>,
+) -> InstallerResult<ThemesOverview> {
+    let overview = list(paths)?;
+    let selected = overview
+        .themes
+        .iter()
+        .find(|installed| installed.name == theme)
+        .ok_or_else(|| {
+            InstallerError::invalid_data(format!("Theme {} is not installed", theme))
+                .with_remediation("Copy the theme folder into Spicetify's Themes folder.")
+        })?;
+
+    let scheme = match color_scheme {
+        Some(requested) => {
+            if !selected
+                .color_schemes
+                .iter()
+                .any(|scheme| scheme.name == requested)
+            {
+                return Err(InstallerError::invalid_data(format!(
+                    "Theme {} has no color scheme {}",
+                    theme, requested
+                )));
+            }
+            requested.to_string()
+        }
+        None => selected
+            .color_schemes
+            .first()
+            .map(|scheme| scheme.name.clone())
+            .unwrap_or_default(),
+    };
+
+    let mut config = paths.load_config()?;
+    config.set("Setting", "current_theme", theme)?;
+    config.set("Setting", "color_scheme", &scheme)?;
+    config.save()?;
+    eprintln!("Selected theme {} with color scheme {:?}", theme, scheme);
+
+    list(paths)
+}
+
+fn read_theme(path: &Path) -> Option<Theme> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let has_user_css = path.join("user.css").is_file();
+    let color_file = path.join(COLOR_FILE);
+
+    // Folders with neither file are not themes
+    if !has_user_css && !color_file.is_file() {
+        return None;
+    }
+
+    let color_schemes = ConfigFile::open(color_file)
+        .map(|colors| {
+            colors
+                .to_view()
+                .sections
+                .into_iter()
+                .map(|section| ColorScheme {
+                    name: section.name,
+                    colors: section
+                        .entries
+                        .into_iter()
+                        .map(|entry| ThemeColor {
+                            name: entry.key,
+                            // Schemes often annotate colors with `; comment`
+                            value: entry
+                                .value
+                                .split(';')
+                                .next()
+                                .unwrap_or("")
+                                .trim()
+                                .to_string(),
+                        })
+                        .collect(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Theme {
+        name,
+        path: path.to_string_lossy().to_string(),
+        color_schemes,
+        has_user_css,
+    })
+}
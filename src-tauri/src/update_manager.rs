@@ -1,10 +1,22 @@
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use tauri::{AppHandle, Emitter};
-use tokio::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{Duration, Instant};
+
+use crate::checksum::{expected_sha256, verify_sha256};
+use crate::download_cache::{self, CacheKey, CachedProduct};
+use crate::error::{ErrorKind, InstallerError, InstallerResult};
+use crate::release_source::{fetch_json, newest_release, ReleaseChannel, ReleaseSource};
+use crate::settings;
+use crate::version::{is_newer, Version};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -12,6 +24,9 @@ pub struct UpdateInfo {
     pub latest_version: String,
     pub download_url: String,
     pub update_available: bool,
+    /// Channel of the offered build, which may be more stable than the one
+    /// the user follows.
+    pub channel: ReleaseChannel,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -19,8 +34,34 @@ pub struct UpdateProgress {
     pub stage: String,
     pub progress: u32,
     pub message: String,
+    /// Set while the installer is downloading.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded_bytes: Option<u64>,
+    /// `None` when the server does not report a length.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_per_second: Option<u64>,
+}
+
+/// A previous installer build kept for rollback.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallerVersion {
+    pub version: String,
+    pub file_name: String,
+    pub path: String,
+    /// Seconds since the Unix epoch when the build was set aside.
+    pub retained_at: u64,
 }
 
+/// Builds kept in the app data directory, most recent first.
+const RETAINED_BUILDS: usize = 3;
+const RETAINED_BUILDS_DIR: &str = "installer_versions";
+/// Written next to each retained build. `fs::copy` keeps the source mtime
+/// on Windows, so the copy's timestamps cannot tell when it was retained.
+const RETAINED_AT_FILE: &str = "retained-at.txt";
+
 pub struct UpdateManager {
     app_handle: AppHandle,
 }
@@ -30,38 +71,28 @@ impl UpdateManager {
         Self { app_handle }
     }
 
-    pub async fn check_for_updates(&self) -> Result<UpdateInfo, String> {
+    pub async fn check_for_updates(&self) -> InstallerResult<UpdateInfo> {
         let current_version = env!("CARGO_PKG_VERSION").to_string();
         
-        // Fetch latest release from GitHub
-        let client = reqwest::Client::builder()
-            .user_agent("Spicetify-Installer-Updater")
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-        let response = client
-            .get("https://api.github.com/repos/FIREPAWER07/SpicetifyInstaller/releases/latest")
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch latest release: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("GitHub API returned status: {}", response.status()));
-        }
-
-        let release_info: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let channel = settings::current().release_channel;
+        let newest = newest_release(
+            &ReleaseSource::current().installer,
+            channel,
+            "Spicetify-Installer-Updater",
+        )
+        .await?;
+        let release_info = newest.release;
 
         let latest_version = release_info["tag_name"]
             .as_str()
-            .ok_or("Missing tag_name")?
+            .ok_or_else(|| InstallerError::invalid_data("Missing tag_name"))?
             .trim_start_matches('v')
             .to_string();
 
         // Find Windows executable asset
-        let assets = release_info["assets"].as_array().ok_or("Missing assets")?;
+        let assets = release_info["assets"]
+            .as_array()
+            .ok_or_else(|| InstallerError::invalid_data("Missing assets"))?;
         let mut download_url = String::new();
 
         for asset in assets {
@@ -69,44 +100,47 @@ impl UpdateManager {
             if name.ends_with(".exe") || name.contains("setup") || name.contains("installer") {
                 download_url = asset["browser_download_url"]
                     .as_str()
-                    .ok_or("Missing download URL")?
+                    .ok_or_else(|| InstallerError::invalid_data("Missing download URL"))?
                     .to_string();
                 break;
             }
         }
 
         if download_url.is_empty() {
-            return Err("No suitable installer found in release assets".to_string());
+            return Err(InstallerError::invalid_data(
+                "No suitable installer found in release assets",
+            ));
         }
 
-        let update_available = self.is_version_newer(&latest_version, &current_version);
+        let update_available = is_newer(&latest_version, &current_version);
 
         Ok(UpdateInfo {
             current_version,
             latest_version,
             download_url,
             update_available,
+            channel: newest.channel,
         })
     }
 
-    pub async fn download_and_install_update(&self, download_url: String) -> Result<(), String> {
-        println!("Starting update process with URL: {}", download_url);
+    pub async fn download_and_install_update(&self, download_url: String) -> InstallerResult<()> {
+        eprintln!("Starting update process with URL: {}", download_url);
         self.emit_progress("Preparing update", 0, "Initializing update process...").await;
 
         // Get current executable path
         let current_exe = env::current_exe()
             .map_err(|e| {
-                let error = format!("Failed to get current executable path: {}", e);
-                println!("Error: {}", error);
+                let error = InstallerError::io("Failed to get current executable path", e);
+                eprintln!("Error: {}", error);
                 error
             })?;
 
-        println!("Current executable path: {:?}", current_exe);
+        eprintln!("Current executable path: {:?}", current_exe);
 
         let current_exe_dir = current_exe.parent()
             .ok_or_else(|| {
-                let error = "Failed to get executable directory".to_string();
-                println!("Error: {}", error);
+                let error = InstallerError::invalid_data("Failed to get executable directory");
+                eprintln!("Error: {}", error);
                 error
             })?;
 
@@ -114,12 +148,12 @@ impl UpdateManager {
         let github_filename = download_url
             .split('/')
             .last()
-            .ok_or("Could not extract filename from URL")?
+            .ok_or_else(|| InstallerError::invalid_data("Could not extract filename from URL"))?
             .split('?')
             .next()
             .unwrap_or("installer.exe");
 
-        println!("GitHub filename: {}", github_filename);
+        eprintln!("GitHub filename: {}", github_filename);
 
         // Create temporary directory for update files
         let temp_dir = env::temp_dir();
@@ -128,76 +162,233 @@ impl UpdateManager {
         // Create update directory if it doesn't exist
         if !update_dir.exists() {
             fs::create_dir_all(&update_dir)
-                .map_err(|e| format!("Failed to create update directory: {}", e))?;
+                .map_err(|e| InstallerError::io("Failed to create update directory", e))?;
         }
 
-        let updater_script_path = update_dir.join("updater.ps1");
         let new_installer_path = update_dir.join(github_filename);
         let final_installer_path = current_exe_dir.join(github_filename);
         
-        println!("Update directory: {:?}", update_dir);
-        println!("Script path: {:?}", updater_script_path);
-        println!("New installer path: {:?}", new_installer_path);
-        println!("Final installer path: {:?}", final_installer_path);
+        eprintln!("Update directory: {:?}", update_dir);
+        eprintln!("New installer path: {:?}", new_installer_path);
+        eprintln!("Final installer path: {:?}", final_installer_path);
+
+        // Release download URLs end in `<tag>/<file name>`
+        let release_tag = download_url.rsplit('/').nth(1).unwrap_or_default();
+        let cache_key = CacheKey::new(CachedProduct::Installer, release_tag, github_filename);
+
+        if let Some(cached_installer) = download_cache::lookup(&cache_key) {
+            self.emit_progress("Downloading", 25, "Using cached installer...").await;
+            fs::copy(&cached_installer, &new_installer_path)
+                .map_err(|e| InstallerError::io("Failed to copy cached installer", e))?;
+            eprintln!("Using cached installer {:?}", cached_installer);
+        } else {
+            self.emit_progress("Downloading", 10, "Downloading new installer...").await;
+
+            self.download_with_resume(&download_url, &new_installer_path)
+                .await
+                .map_err(|error| {
+                    eprintln!("Download error: {}", error);
+                    error
+                })?;
+
+            eprintln!("New installer written to disk");
+
+            self.emit_progress("Verifying", 25, "Verifying download checksum...").await;
+
+            if let Err(error) = self.verify_download(&download_url, &new_installer_path).await {
+                eprintln!("Error: {}", error);
+                let _ = fs::remove_file(&new_installer_path);
+                return Err(error);
+            }
 
-        self.emit_progress("Downloading", 10, "Downloading new installer...").await;
+            eprintln!("Checksum verified");
 
-        // Download the new installer
-        let client = reqwest::Client::builder()
-            .user_agent("Spicetify-Installer-Updater")
-            .timeout(Duration::from_secs(300))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+            // Only verified builds are cached
+            if let Err(error) = download_cache::store_copy(&cache_key, &new_installer_path) {
+                eprintln!("Failed to cache installer: {}", error);
+            }
+        }
 
-        println!("Starting download from: {}", download_url);
-        
-        let response = client.get(&download_url).send().await
-            .map_err(|e| {
-                let error = format!("Failed to download update: {}", e);
-                println!("Download error: {}", error);
-                error
-            })?;
+        let backup_path = self.retain_current_build(&current_exe).unwrap_or_else(|error| {
+            eprintln!("Failed to keep the current build for rollback: {}", error);
+            update_dir.join("installer_backup.exe")
+        });
 
-        if !response.status().is_success() {
-            let error = format!("Download failed with status: {}", response.status());
-            println!("Error: {}", error);
-            return Err(error);
-        }
+        self.swap_installer(
+            &current_exe,
+            &new_installer_path,
+            &final_installer_path,
+            &backup_path,
+            &update_dir,
+        )
+        .await
+    }
 
-        println!("Download response received, reading content...");
+    /// Previous installer builds that can be rolled back to, most recently
+    /// replaced first.
+    pub fn list_installer_versions(&self) -> InstallerResult<Vec<InstallerVersion>> {
+        let versions_dir = self.retained_builds_dir()?;
+        let Ok(dirs) = fs::read_dir(&versions_dir) else {
+            return Ok(Vec::new());
+        };
 
-        let content = response.bytes().await
-            .map_err(|e| {
-                let error = format!("Failed to read download content: {}", e);
-                println!("Error: {}", error);
-                error
-            })?;
+        let mut versions: Vec<InstallerVersion> = dirs
+            .flatten()
+            .filter_map(|dir| {
+                let version = Version::parse(&dir.file_name().to_string_lossy())?;
+                let build = fs::read_dir(dir.path())
+                    .ok()?
+                    .flatten()
+                    .find(|entry| {
+                        entry.path().is_file() && entry.file_name() != RETAINED_AT_FILE
+                    })?;
+                // Builds without a record sort last and are pruned first
+                let retained_at = fs::read_to_string(dir.path().join(RETAINED_AT_FILE))
+                    .ok()
+                    .and_then(|text| text.trim().parse().ok())
+                    .unwrap_or(0);
+
+                Some(InstallerVersion {
+                    version: version.to_string(),
+                    file_name: build.file_name().to_string_lossy().to_string(),
+                    path: build.path().to_string_lossy().to_string(),
+                    retained_at,
+                })
+            })
+            .collect();
 
-        println!("Downloaded {} bytes", content.len());
+        versions.sort_by_key(|version| Reverse(version.retained_at));
+        Ok(versions)
+    }
 
-        fs::write(&new_installer_path, content)
-            .map_err(|e| {
-                let error = format!("Failed to write installer: {}", e);
-                println!("Error: {}", error);
-                error
+    /// Swaps the running installer for a retained build and restarts into
+    /// it. The build being replaced is retained in turn, so a rollback can
+    /// itself be undone.
+    pub async fn rollback_installer(&self, version: &str) -> InstallerResult<()> {
+        let requested = Version::parse(version).ok_or_else(|| {
+            InstallerError::invalid_data(format!("Invalid installer version: {}", version))
+        })?;
+        if Version::parse(env!("CARGO_PKG_VERSION")) == Some(requested.clone()) {
+            return Err(InstallerError::invalid_data(format!(
+                "Installer v{} is already running",
+                requested
+            )));
+        }
+
+        let target = self
+            .list_installer_versions()?
+            .into_iter()
+            .find(|build| Version::parse(&build.version).as_ref() == Some(&requested))
+            .ok_or_else(|| {
+                InstallerError::invalid_data(format!("No retained installer build for v{}", requested))
+                    .with_remediation("Only builds replaced by an update or rollback can be restored.")
             })?;
 
-        println!("New installer written to disk");
+        eprintln!("Rolling back installer to v{} from {}", target.version, target.path);
+        self.emit_progress("Preparing rollback", 0, &format!("Restoring v{}...", target.version)).await;
+
+        let current_exe = env::current_exe()
+            .map_err(|e| InstallerError::io("Failed to get current executable path", e))?;
+        let current_exe_dir = current_exe
+            .parent()
+            .ok_or_else(|| InstallerError::invalid_data("Failed to get executable directory"))?;
+
+        let update_dir = env::temp_dir().join("spicetify_installer_update");
+        fs::create_dir_all(&update_dir)
+            .map_err(|e| InstallerError::io("Failed to create update directory", e))?;
+
+        // Copy out first: retaining the current build may prune the target
+        let new_installer_path = update_dir.join(&target.file_name);
+        fs::copy(&target.path, &new_installer_path)
+            .map_err(|e| InstallerError::io("Failed to copy retained installer", e))?;
+        let final_installer_path = current_exe_dir.join(&target.file_name);
+
+        let backup_path = self.retain_current_build(&current_exe).unwrap_or_else(|error| {
+            eprintln!("Failed to keep the current build for rollback: {}", error);
+            update_dir.join("installer_backup.exe")
+        });
+
+        self.swap_installer(
+            &current_exe,
+            &new_installer_path,
+            &final_installer_path,
+            &backup_path,
+            &update_dir,
+        )
+        .await
+    }
+
+    fn retained_builds_dir(&self) -> InstallerResult<PathBuf> {
+        let data_dir = self.app_handle.path().app_data_dir().map_err(|e| {
+            InstallerError::invalid_data("Failed to resolve the app data directory").with_detail(e)
+        })?;
+        Ok(data_dir.join(RETAINED_BUILDS_DIR))
+    }
+
+    /// Copies the running build into the retained builds folder and prunes
+    /// it to the newest `RETAINED_BUILDS`. Returns the copy, which the
+    /// updater script restores from if the swap fails.
+    fn retain_current_build(&self, current_exe: &Path) -> InstallerResult<PathBuf> {
+        let file_name = current_exe
+            .file_name()
+            .ok_or_else(|| InstallerError::invalid_data("Executable path has no file name"))?;
+        let build_dir = self.retained_builds_dir()?.join(env!("CARGO_PKG_VERSION"));
+
+        let _ = fs::remove_dir_all(&build_dir);
+        fs::create_dir_all(&build_dir)
+            .map_err(|e| InstallerError::io("Failed to create retained builds directory", e))?;
+        let retained = build_dir.join(file_name);
+        fs::copy(current_exe, &retained)
+            .map_err(|e| InstallerError::io("Failed to retain the current installer", e))?;
+        let retained_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        fs::write(build_dir.join(RETAINED_AT_FILE), retained_at.to_string())
+            .map_err(|e| InstallerError::io("Failed to record when the installer was retained", e))?;
+        eprintln!("Retained current installer at {:?}", retained);
+
+        for stale in self.list_installer_versions()?.into_iter().skip(RETAINED_BUILDS) {
+            eprintln!("Removing retained installer v{}", stale.version);
+            if let Some(dir) = Path::new(&stale.path).parent() {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+
+        Ok(retained)
+    }
+
+    /// Hands over to the updater script, which waits for this process to
+    /// exit, puts `new_installer_path` in place as `final_installer_path` and
+    /// starts it. Used for both updates and rollbacks.
+    async fn swap_installer(
+        &self,
+        current_exe: &Path,
+        new_installer_path: &Path,
+        final_installer_path: &Path,
+        backup_path: &Path,
+        update_dir: &Path,
+    ) -> InstallerResult<()> {
+        let current_exe_dir = current_exe
+            .parent()
+            .ok_or_else(|| InstallerError::invalid_data("Failed to get executable directory"))?;
+        let updater_script_path = update_dir.join("updater.ps1");
 
         self.emit_progress("Creating updater", 30, "Creating update script...").await;
 
         // Create the PowerShell updater script
         let updater_script = self.create_updater_script(
-            &current_exe,
-            &new_installer_path,
-            &final_installer_path,
+            current_exe,
+            new_installer_path,
+            final_installer_path,
+            backup_path,
             current_exe_dir,
         )?;
 
         fs::write(&updater_script_path, updater_script)
             .map_err(|e| {
-                let error = format!("Failed to write updater script: {}", e);
-                println!("Error: {}", error);
+                let error = InstallerError::io("Failed to write updater script", e);
+                eprintln!("Error: {}", error);
                 error
             })?;
 
@@ -212,9 +403,9 @@ powershell.exe -ExecutionPolicy Bypass -WindowStyle Hidden -File "{}"
 
         let batch_path = update_dir.join("run_updater.bat");
         fs::write(&batch_path, batch_script)
-            .map_err(|e| format!("Failed to write batch script: {}", e))?;
+            .map_err(|e| InstallerError::io("Failed to write batch script", e))?;
 
-        println!("Updater script and batch file created");
+        eprintln!("Updater script and batch file created");
 
         self.emit_progress("Executing update", 50, "Starting update process...").await;
 
@@ -228,23 +419,23 @@ powershell.exe -ExecutionPolicy Bypass -WindowStyle Hidden -File "{}"
             cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
         }
 
-        println!("Executing updater batch file...");
+        eprintln!("Executing updater batch file...");
 
         cmd.spawn()
             .map_err(|e| {
-                let error = format!("Failed to start updater: {}", e);
-                println!("Error: {}", error);
+                let error = InstallerError::io("Failed to start updater", e);
+                eprintln!("Error: {}", error);
                 error
             })?;
 
         self.emit_progress("Completing", 90, "Update process started. Application will restart...").await;
 
-        println!("Updater started, waiting before exit...");
+        eprintln!("Updater started, waiting before exit...");
 
         // Give the updater more time to start
         tokio::time::sleep(Duration::from_millis(3000)).await;
 
-        println!("Exiting application for update...");
+        eprintln!("Exiting application for update...");
 
         // Exit the current application
         self.app_handle.exit(0);
@@ -252,13 +443,190 @@ powershell.exe -ExecutionPolicy Bypass -WindowStyle Hidden -File "{}"
         Ok(())
     }
 
+    /// Streams the installer to `<destination>.part` and renames it once
+    /// complete. A `.part` file left by an interrupted attempt is resumed with
+    /// an HTTP Range request; servers that ignore the range restart from zero.
+    async fn download_with_resume(
+        &self,
+        download_url: &str,
+        destination: &Path,
+    ) -> InstallerResult<()> {
+        let part_path = PathBuf::from(format!("{}.part", destination.to_string_lossy()));
+
+        let client = reqwest::Client::builder()
+            .user_agent("Spicetify-Installer-Updater")
+            .connect_timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| InstallerError::network("Failed to create HTTP client", e))?;
+
+        let mut resume_from = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+        let mut request = client.get(download_url);
+        if resume_from > 0 {
+            eprintln!("Resuming download from byte {}", resume_from);
+            request = request.header(RANGE, format!("bytes={}-", resume_from));
+        }
+
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| InstallerError::network("Failed to download update", e))?;
+
+        // The part file already holds the whole body, or is stale.
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            eprintln!("Server rejected the resume range, restarting download");
+            let _ = fs::remove_file(&part_path);
+            resume_from = 0;
+            response = client
+                .get(download_url)
+                .send()
+                .await
+                .map_err(|e| InstallerError::network("Failed to download update", e))?;
+        }
+
+        if !response.status().is_success() {
+            return Err(InstallerError::http_status("Update download failed", &response));
+        }
+
+        let resumed = resume_from > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            resume_from = 0;
+        }
+
+        let total_bytes = response.content_length().map(|length| length + resume_from);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&part_path)
+            .map_err(|e| InstallerError::io("Failed to open download file", e))?;
+
+        let started = Instant::now();
+        let mut downloaded = resume_from;
+        let mut last_emit = Instant::now();
+
+        loop {
+            let chunk = tokio::time::timeout(Duration::from_secs(60), response.chunk())
+                .await
+                .map_err(|_| {
+                    InstallerError::new(ErrorKind::Network, "Update download stalled")
+                        .with_remediation("Try again; the download resumes where it stopped.")
+                })?
+                .map_err(|e| InstallerError::network("Failed to read download content", e))?;
+
+            let Some(chunk) = chunk else { break };
+            file.write_all(&chunk)
+                .map_err(|e| InstallerError::io("Failed to write installer", e))?;
+            downloaded += chunk.len() as u64;
+
+            if last_emit.elapsed() >= Duration::from_millis(250) {
+                last_emit = Instant::now();
+                let elapsed = started.elapsed().as_secs_f64().max(0.001);
+                let speed = ((downloaded - resume_from) as f64 / elapsed) as u64;
+                self.emit_download_progress(downloaded, total_bytes, speed);
+            }
+        }
+
+        file.flush()
+            .map_err(|e| InstallerError::io("Failed to write installer", e))?;
+        drop(file);
+
+        if let Some(total) = total_bytes {
+            if downloaded != total {
+                return Err(
+                    InstallerError::new(ErrorKind::Network, "Update download was incomplete")
+                        .with_detail(format!("Received {} of {} bytes", downloaded, total))
+                        .with_remediation("Try again; the download resumes where it stopped."),
+                );
+            }
+        }
+
+        let elapsed = started.elapsed().as_secs_f64().max(0.001);
+        let speed = ((downloaded - resume_from) as f64 / elapsed) as u64;
+        self.emit_download_progress(downloaded, total_bytes, speed);
+        eprintln!("Downloaded {} bytes", downloaded);
+
+        fs::rename(&part_path, destination)
+            .map_err(|e| InstallerError::io("Failed to move downloaded installer into place", e))
+    }
+
+    fn emit_download_progress(&self, downloaded: u64, total: Option<u64>, bytes_per_second: u64) {
+        let (progress, message) = match total {
+            Some(total) if total > 0 => (
+                10 + (downloaded * 15 / total) as u32,
+                format!(
+                    "Downloading new installer... {} / {} ({}/s)",
+                    format_bytes(downloaded),
+                    format_bytes(total),
+                    format_bytes(bytes_per_second)
+                ),
+            ),
+            _ => (
+                10,
+                format!(
+                    "Downloading new installer... {} ({}/s)",
+                    format_bytes(downloaded),
+                    format_bytes(bytes_per_second)
+                ),
+            ),
+        };
+
+        let update_progress = UpdateProgress {
+            stage: "Downloading".to_string(),
+            progress,
+            message,
+            downloaded_bytes: Some(downloaded),
+            total_bytes: total,
+            bytes_per_second: Some(bytes_per_second),
+        };
+
+        let _ = self.app_handle.emit("update_progress", &update_progress);
+    }
+
+    /// Verifies the downloaded installer against the SHA-256 published with
+    /// its release. Updates without a published checksum are refused.
+    async fn verify_download(&self, download_url: &str, path: &Path) -> InstallerResult<()> {
+        let releases = fetch_json(
+            &ReleaseSource::current().installer.releases_url(),
+            "Spicetify-Installer-Updater",
+        )
+        .await?;
+
+        let (release, asset) = releases
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|release| {
+                release["assets"]
+                    .as_array()?
+                    .iter()
+                    .find(|asset| asset["browser_download_url"].as_str() == Some(download_url))
+                    .map(|asset| (release, asset))
+            })
+            .ok_or_else(|| {
+                InstallerError::invalid_data("The update is not an asset of a published release")
+                    .with_detail(download_url)
+            })?;
+
+        let expected = expected_sha256(release, asset, "Spicetify-Installer-Updater")
+            .await?
+            .ok_or_else(|| {
+                InstallerError::invalid_data("No checksum is published for this update")
+                    .with_remediation("Download the installer manually from the releases page.")
+            })?;
+
+        verify_sha256(path, &expected)
+    }
+
     fn create_updater_script(
         &self,
-        current_exe: &PathBuf,
-        new_installer: &PathBuf,
-        final_installer: &PathBuf,
-        install_dir: &std::path::Path,
-    ) -> Result<String, String> {
+        current_exe: &Path,
+        new_installer: &Path,
+        final_installer: &Path,
+        backup_path: &Path,
+        install_dir: &Path,
+    ) -> InstallerResult<String> {
+    let escape = |path: &Path| path.to_string_lossy().replace('\\', "\\\\");
     let script = format!(r#"
 # Spicetify Installer Auto-Updater Script
 # Downloads latest version and installs with GitHub filename
@@ -267,11 +635,11 @@ $ErrorActionPreference = 'Continue'
 $ProgressPreference = 'SilentlyContinue'
 
 # Configuration
-$CurrentInstaller = "{}"
-$NewInstaller = "{}"
-$FinalInstaller = "{}"
-$InstallDirectory = "{}"
-$BackupPath = "$env:TEMP\spicetify_installer_backup_$(Get-Date -Format 'yyyyMMdd_HHmmss').exe"
+$CurrentInstaller = "{current_exe}"
+$NewInstaller = "{new_installer}"
+$FinalInstaller = "{final_installer}"
+$InstallDirectory = "{install_dir}"
+$BackupPath = "{backup_path}"
 $LogFile = "$env:TEMP\spicetify_updater_$(Get-Date -Format 'yyyyMMdd_HHmmss').log"
 
 # Logging function
@@ -314,8 +682,8 @@ try {{
     $NewSize = (Get-Item $NewInstaller).Length
     Write-Log "New installer size: $NewSize bytes"
 
-    # Create backup of current installer
-    if (Test-Path $CurrentInstaller) {{
+    # Create backup of current installer unless the app already kept one
+    if ((Test-Path $CurrentInstaller) -and -not (Test-Path $BackupPath)) {{
         Write-Log "Creating backup of current installer..."
         Copy-Item -Path $CurrentInstaller -Destination $BackupPath -Force
         Write-Log "Backup created: $BackupPath"
@@ -407,10 +775,11 @@ catch {{
 Write-Log "Update script completed successfully"
 exit 0
 "#,
-        current_exe.to_string_lossy().replace('\\', "\\\\"),
-        new_installer.to_string_lossy().replace('\\', "\\\\"),
-        final_installer.to_string_lossy().replace('\\', "\\\\"),
-        install_dir.to_string_lossy().replace('\\', "\\\\")
+        current_exe = escape(current_exe),
+        new_installer = escape(new_installer),
+        final_installer = escape(final_installer),
+        install_dir = escape(install_dir),
+        backup_path = escape(backup_path),
     );
 
     Ok(script)
@@ -421,30 +790,27 @@ exit 0
             stage: stage.to_string(),
             progress,
             message: message.to_string(),
+            downloaded_bytes: None,
+            total_bytes: None,
+            bytes_per_second: None,
         };
 
         let _ = self.app_handle.emit("update_progress", &update_progress);
     }
+}
 
-    fn is_version_newer(&self, latest: &str, current: &str) -> bool {
-        let latest_parts: Vec<u32> = latest.split('.')
-            .filter_map(|s| s.parse().ok())
-            .collect();
-        let current_parts: Vec<u32> = current.split('.')
-            .filter_map(|s| s.parse().ok())
-            .collect();
-
-        for i in 0..std::cmp::max(latest_parts.len(), current_parts.len()) {
-            let latest_part = latest_parts.get(i).unwrap_or(&0);
-            let current_part = current_parts.get(i).unwrap_or(&0);
-
-            if latest_part > current_part {
-                return true;
-            } else if latest_part < current_part {
-                return false;
-            }
-        }
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
 
-        false
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
     }
 }
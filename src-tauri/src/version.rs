@@ -0,0 +1,300 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A semantic version such as `1.0.7-beta.2+build.5`, ordered by semver
+/// precedence. Release tags are parsed leniently: a leading `v` or a
+/// `spicetify v` prefix is accepted, missing minor/patch components count as
+/// zero, and extra numeric components (`1.2.3.4`) are compared too.
+#[derive(Debug, Clone)]
+pub struct Version {
+    core: Vec<u64>,
+    prerelease: Vec<Identifier>,
+    build: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl Version {
+    /// Returns `None` when the text is not a version.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.rsplit(' ').next().unwrap_or(text);
+        let text = text.strip_prefix(['v', 'V']).unwrap_or(text);
+
+        let (text, build) = match text.split_once('+') {
+            Some((text, build)) if is_dotted_identifiers(build) => (text, Some(build.to_string())),
+            Some(_) => return None,
+            None => (text, None),
+        };
+
+        let (core, prerelease) = match text.split_once('-') {
+            Some((core, prerelease)) if is_dotted_identifiers(prerelease) => (core, prerelease),
+            Some(_) => return None,
+            None => (text, ""),
+        };
+
+        let core = core
+            .split('.')
+            .map(|part| {
+                if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                    None
+                } else {
+                    part.parse::<u64>().ok()
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let prerelease = if prerelease.is_empty() {
+            Vec::new()
+        } else {
+            prerelease.split('.').map(Identifier::parse).collect()
+        };
+
+        Some(Self {
+            core,
+            prerelease,
+            build,
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.prerelease.is_empty()
+    }
+
+    /// The first prerelease identifier, e.g. `beta` for `1.0.7-beta.2`.
+    pub fn prerelease_label(&self) -> Option<&str> {
+        match self.prerelease.first()? {
+            Identifier::Alphanumeric(label) => Some(label),
+            Identifier::Numeric(_) => None,
+        }
+    }
+
+    fn core_part(&self, index: usize) -> u64 {
+        self.core.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl Identifier {
+    fn parse(text: &str) -> Self {
+        if text.bytes().all(|b| b.is_ascii_digit()) {
+            if let Ok(number) = text.parse() {
+                return Identifier::Numeric(number);
+            }
+        }
+        Identifier::Alphanumeric(text.to_string())
+    }
+}
+
+fn is_dotted_identifiers(text: &str) -> bool {
+    !text.is_empty()
+        && text.split('.').all(|part| {
+            !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+impl Ord for Identifier {
+    /// Numeric identifiers sort numerically and below alphanumeric ones,
+    /// which sort in ASCII order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::Alphanumeric(_)) => Ordering::Less,
+            (Identifier::Alphanumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alphanumeric(a), Identifier::Alphanumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    /// Semver precedence: core components first, then a prerelease sorts
+    /// below the matching release. Build metadata is ignored.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.core.len().max(other.core.len());
+        let core = (0..len)
+            .map(|i| self.core_part(i).cmp(&other.core_part(i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal);
+        if core.is_ne() {
+            return core;
+        }
+
+        match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.prerelease.cmp(&other.prerelease),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let core: Vec<String> = self.core.iter().map(u64::to_string).collect();
+        write!(f, "{}", core.join("."))?;
+
+        if !self.prerelease.is_empty() {
+            let prerelease: Vec<String> = self
+                .prerelease
+                .iter()
+                .map(|identifier| match identifier {
+                    Identifier::Numeric(number) => number.to_string(),
+                    Identifier::Alphanumeric(text) => text.clone(),
+                })
+                .collect();
+            write!(f, "-{}", prerelease.join("."))?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `latest` is strictly newer than `current`. Unparseable versions
+/// never count as an update.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (Version::parse(latest), Version::parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        // (lower, higher)
+        let cases = [
+            ("1.0.6", "1.0.7"),
+            ("1.0.7-alpha", "1.0.7"),
+            ("1.0.7-alpha", "1.0.7-alpha.1"),
+            ("1.0.7-alpha.1", "1.0.7-alpha.beta"),
+            ("1.0.7-alpha.beta", "1.0.7-beta"),
+            ("1.0.7-beta", "1.0.7-beta.2"),
+            ("1.0.7-beta.2", "1.0.7-beta.11"),
+            ("1.0.7-beta.11", "1.0.7-rc.1"),
+            ("1.0.7-rc.1", "1.0.7"),
+            ("1.0.7", "1.0.8-alpha"),
+            ("1.9.0", "1.10.0"),
+            ("2.16.2", "2.36.0"),
+            ("1.2.3", "1.2.3.1"),
+            ("0.9", "1"),
+        ];
+
+        for (lower, higher) in cases {
+            let lower_version = Version::parse(lower).unwrap();
+            let higher_version = Version::parse(higher).unwrap();
+            assert!(lower_version < higher_version, "{} < {}", lower, higher);
+            assert!(
+                is_newer(higher, lower),
+                "{} is newer than {}",
+                higher,
+                lower
+            );
+            assert!(
+                !is_newer(lower, higher),
+                "{} is not newer than {}",
+                lower,
+                higher
+            );
+        }
+    }
+
+    #[test]
+    fn equality() {
+        let cases = [
+            ("1.0.7", "v1.0.7"),
+            ("2.36", "2.36.0"),
+            ("1.0.7+build.5", "1.0.7"),
+            ("1.0.7-alpha+001", "1.0.7-alpha+002"),
+            ("spicetify v2.36.0", "2.36.0"),
+            ("  1.0.7\n", "1.0.7"),
+        ];
+
+        for (a, b) in cases {
+            assert_eq!(
+                Version::parse(a).unwrap(),
+                Version::parse(b).unwrap(),
+                "{} == {}",
+                a,
+                b
+            );
+            assert!(!is_newer(a, b), "{} is not newer than {}", a, b);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let cases = [
+            "",
+            "v",
+            "latest",
+            "1..2",
+            "1.x.3",
+            "1.0.7-",
+            "1.0.7+",
+            "1.0.7-al!pha",
+        ];
+
+        for text in cases {
+            assert!(
+                Version::parse(text).is_none(),
+                "{:?} should not parse",
+                text
+            );
+            assert!(!is_newer(text, "1.0.0"), "{:?} is never newer", text);
+        }
+    }
+
+    #[test]
+    fn prerelease_label() {
+        let cases = [
+            ("1.0.7", None),
+            ("1.0.7-alpha", Some("alpha")),
+            ("1.0.7-beta.2", Some("beta")),
+            ("1.0.7-1", None),
+        ];
+
+        for (text, label) in cases {
+            assert_eq!(
+                Version::parse(text).unwrap().prerelease_label(),
+                label,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["1.0.7", "1.0.7-beta.2", "1.0.7-alpha+build.5", "2.36"] {
+            assert_eq!(Version::parse(text).unwrap().to_string(), text);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+export interface InstallerError {
+  kind: string
+  message: string
+  detail?: string
+  remediation?: string
+}
+
+export function isInstallerError(error: unknown): error is InstallerError {
+  return typeof error === "object" && error !== null && "kind" in error && "message" in error
+}
+
+export function formatInstallerError(error: unknown): string {
+  if (!isInstallerError(error)) {
+    return `${error}`
+  }
+
+  let text = error.message
+  if (error.detail) {
+    text += `\n${error.detail}`
+  }
+  if (error.remediation) {
+    text += `\n${error.remediation}`
+  }
+  return text
+}
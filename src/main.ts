@@ -1,11 +1,25 @@
 import { invoke } from "@tauri-apps/api/core"
 import { listen } from "@tauri-apps/api/event"
 import { DropdownHandler } from "./dropdown-handler"
-import { UpdateManager, type UpdateInfo, type UpdateProgress } from "./update-manager"
+import { formatInstallerError, isInstallerError } from "./errors"
+import { UpdateManager, type ReleaseChannel, type UpdateInfo, type UpdateProgress } from "./update-manager"
+
+type Operation =
+  | { type: "install"; version?: string }
+  | { type: "installFromArchive"; archivePath: string }
+  | { type: "upgrade" }
+  | { type: "uninstall"; keepThemes?: boolean }
+  | { type: "repair" }
+  | { type: "backup" }
+  | { type: "apply" }
+  | { type: "restore" }
+  | { type: "installMarketplace" }
+  | { type: "uninstallMarketplace" }
 
 interface SpicetifyCommand {
   name: string
   command: string
+  operation: Operation
   description: string
 }
 
@@ -16,6 +30,105 @@ interface VersionInfo {
   hasSpicetifyUpdate: boolean
   latestInstallerVersion: string | null
   latestInstallerUrl: string | null
+  latestSpicetifyVersion: string | null
+  spicetifyProbe: SpicetifyProbe
+}
+
+interface SpicetifyRelease {
+  version: string
+  prerelease: boolean
+  publishedAt: string | null
+}
+
+interface SpicetifyProbe {
+  path: string | null
+  version: string | null
+  method: "knownDirectory" | "pathSearch" | null
+  attempts: { method: "knownDirectory" | "pathSearch"; path: string; error?: string }[]
+}
+
+interface CacheSummary {
+  path: string
+  entries: {
+    product: "spicetify" | "installer" | "marketplace"
+    version: string
+    os: string
+    arch: string
+    fileName: string
+    sha256: string
+    size: number
+    lastUsed: number
+  }[]
+  totalBytes: number
+  limitBytes: number
+}
+
+interface ThemesOverview {
+  currentTheme: string | null
+  colorScheme: string | null
+  themes: {
+    name: string
+    path: string
+    colorSchemes: { name: string; colors: { name: string; value: string }[] }[]
+    hasUserCss: boolean
+  }[]
+}
+
+interface Extension {
+  name: string
+  path: string | null
+  bundled: boolean
+  status: "enabled" | "disabled" | "missing"
+}
+
+interface CustomApp {
+  name: string
+  displayName: string | null
+  path: string | null
+  bundled: boolean
+  status: "enabled" | "disabled" | "missing"
+  problem: string | null
+}
+
+interface MarketplaceStatus {
+  installed: boolean
+  enabled: boolean
+  version: string | null
+  latestVersion: string | null
+  updateAvailable: boolean
+  path: string | null
+  problem: string | null
+}
+
+interface SetupProfile {
+  name: string
+  savedAt: number
+  currentTheme: string
+  colorScheme: string
+  extensions: string[]
+  customApps: string[]
+  values: { section: string; key: string; value: string }[]
+}
+
+interface SpicetifyConfig {
+  path: string
+  sections: { name: string; entries: { key: string; value: string }[] }[]
+}
+
+interface OperationSummary {
+  id: string
+  operation: string
+  startedAt: number
+  endedAt: number
+  status: "succeeded" | "failed" | "cancelled"
+  exitCode: number | null
+  error?: string
+  spicetifyVersion: string | null
+  spotifyVersion: string | null
+}
+
+interface OperationRecord extends OperationSummary {
+  transcript: { stream: string; line: string }[]
 }
 
 class LoadingManager {
@@ -238,19 +351,63 @@ class SpicetifyInstallerApp {
     faqButton: document.getElementById("open-faq")!,
     faqModal: document.getElementById("faq-modal")!,
     closeFaqButton: document.getElementById("close-faq")!,
+    historyButton: document.getElementById("open-history")!,
+    themesButton: document.getElementById("open-themes")!,
+    themesModal: document.getElementById("themes-modal")!,
+    closeThemesButton: document.getElementById("close-themes")!,
+    themesList: document.getElementById("themes-list")!,
+    extensionsButton: document.getElementById("open-extensions")!,
+    extensionsModal: document.getElementById("extensions-modal")!,
+    closeExtensionsButton: document.getElementById("close-extensions")!,
+    extensionsList: document.getElementById("extensions-list")!,
+    extensionPathInput: document.getElementById("extension-path-input") as HTMLInputElement,
+    installExtensionButton: document.getElementById("install-extension") as HTMLButtonElement,
+    appsButton: document.getElementById("open-apps")!,
+    appsModal: document.getElementById("apps-modal")!,
+    closeAppsButton: document.getElementById("close-apps")!,
+    appsList: document.getElementById("apps-list")!,
+    appPathInput: document.getElementById("app-path-input") as HTMLInputElement,
+    installAppButton: document.getElementById("install-app") as HTMLButtonElement,
+    profilesButton: document.getElementById("open-profiles")!,
+    profilesModal: document.getElementById("profiles-modal")!,
+    closeProfilesButton: document.getElementById("close-profiles")!,
+    profilesList: document.getElementById("profiles-list")!,
+    profileNameInput: document.getElementById("profile-name-input") as HTMLInputElement,
+    saveProfileButton: document.getElementById("save-profile") as HTMLButtonElement,
+    configButton: document.getElementById("open-config")!,
+    configModal: document.getElementById("config-modal")!,
+    closeConfigButton: document.getElementById("close-config")!,
+    configSections: document.getElementById("config-sections")!,
+    configAutoApply: document.getElementById("config-auto-apply") as HTMLInputElement,
+    exportDiagnosticsButton: document.getElementById("export-diagnostics") as HTMLButtonElement,
+    historyModal: document.getElementById("history-modal")!,
+    closeHistoryButton: document.getElementById("close-history")!,
+    historyList: document.getElementById("history-list")!,
+    historyTranscript: document.getElementById("history-transcript")!,
     appVersionElement: document.getElementById("app-version")!,
     spicetifyVersionElement: document.getElementById("spicetify-version")!,
+    marketplaceStatusElement: document.getElementById("marketplace-status")!,
     updateNotificationElement: document.getElementById("update-notification")!,
     footerVersionElement: document.getElementById("footer-version")!,
+    releaseChannelSelect: document.getElementById("release-channel") as HTMLSelectElement,
+    clearCacheButton: document.getElementById("clear-download-cache") as HTMLButtonElement,
+    rollbackPicker: document.getElementById("installer-rollback-picker")!,
+    rollbackSelect: document.getElementById("installer-rollback") as HTMLSelectElement,
+    versionPicker: document.getElementById("spicetify-version-picker")!,
+    versionSelect: document.getElementById("spicetify-version-select") as HTMLSelectElement,
+    archivePicker: document.getElementById("archive-path-picker")!,
+    archivePathInput: document.getElementById("archive-path-input") as HTMLInputElement,
   }
 
   private dropdownHandler: DropdownHandler
   private updateManager: UpdateManager
   private selectedCommand: string | null = null
+  private selectedOperation: Operation | null = null
+  private versionsLoaded = false
   private isExecuting = false
   private currentProgress = 0
-  private progressInterval: number | null = null
   private progressListener: any = null
+  private outputListener: any = null
   private versionComparisonCache = new Map<string, boolean>()
   private updateClickTimeout: number | null = null
   private outputBuffer = ""
@@ -261,22 +418,50 @@ class SpicetifyInstallerApp {
     {
       name: "INSTALL",
       command: "iwr -useb https://raw.githubusercontent.com/spicetify/spicetify-cli/master/install.ps1 | iex",
+      operation: { type: "install" },
       description: "Installs or updates Spicetify CLI on your system",
     },
+    {
+      name: "INSTALL FROM FILE",
+      command: "Install from local archive",
+      operation: { type: "installFromArchive", archivePath: "" },
+      description: "Installs Spicetify offline from a downloaded release archive",
+    },
+    {
+      name: "UPGRADE",
+      command: "spicetify upgrade",
+      operation: { type: "upgrade" },
+      description: "Replaces the installed Spicetify CLI with the latest release",
+    },
     {
       name: "REPAIR",
       command: "spicetify restore backup apply",
+      operation: { type: "repair" },
       description: "Restores from backup and applies Spicetify",
     },
     {
       name: "BACKUP",
       command: "spicetify backup",
+      operation: { type: "backup" },
       description: "Creates a backup of your Spotify installation",
     },
+    {
+      name: "MARKETPLACE",
+      command: "Install Spicetify Marketplace",
+      operation: { type: "installMarketplace" },
+      description: "Installs or updates the Spicetify Marketplace custom app",
+    },
+    {
+      name: "REMOVE MARKETPLACE",
+      command: "Remove Spicetify Marketplace",
+      operation: { type: "uninstallMarketplace" },
+      description: "Removes Marketplace and keeps the rest of Spicetify",
+    },
     {
       name: "UNINSTALL",
       command:
         'spicetify restore; Remove-Item -Path "$env:APPDATA\\spicetify" -Recurse -Force -ErrorAction SilentlyContinue; Remove-Item -Path "$env:LOCALAPPDATA\\spicetify" -Recurse -Force -ErrorAction SilentlyContinue',
+      operation: { type: "uninstall" },
       description: "Completely removes Spicetify and restores Spotify to its original state",
     },
   ]
@@ -340,6 +525,72 @@ class SpicetifyInstallerApp {
     return new Promise((resolve) => setTimeout(resolve, ms))
   }
 
+  private async initReleaseChannel(): Promise<void> {
+    try {
+      this.dom.releaseChannelSelect.value = await invoke<ReleaseChannel>("get_release_channel")
+    } catch (error) {
+      console.error("Failed to load release channel:", error)
+    }
+
+    this.dom.releaseChannelSelect.addEventListener("change", async () => {
+      try {
+        await invoke("set_release_channel", { channel: this.dom.releaseChannelSelect.value })
+        await this.checkForAppUpdates()
+      } catch (error) {
+        this.appendOutput(`Failed to change update channel: ${formatInstallerError(error)}\n`)
+      }
+    })
+  }
+
+  private async initInstallerRollback(): Promise<void> {
+    const versions = await this.updateManager.listInstallerVersions()
+    for (const build of versions) {
+      const option = document.createElement("option")
+      option.value = build.version
+      option.textContent = `v${build.version}`
+      this.dom.rollbackSelect.appendChild(option)
+    }
+    this.dom.rollbackPicker.classList.toggle("hidden", versions.length === 0)
+
+    this.dom.rollbackSelect.addEventListener("change", async () => {
+      const version = this.dom.rollbackSelect.value
+      if (!version) return
+      if (!confirm(`Replace this installer with v${version}? The app will restart.`)) {
+        this.dom.rollbackSelect.value = ""
+        return
+      }
+      try {
+        await this.updateManager.rollbackInstaller(version)
+      } catch (error) {
+        this.dom.rollbackSelect.value = ""
+        this.appendOutput(`${error instanceof Error ? error.message : String(error)}\n`)
+      }
+    })
+  }
+
+  private async initDownloadCache(): Promise<void> {
+    try {
+      this.showCacheSize(await invoke<CacheSummary>("get_download_cache"))
+    } catch (error) {
+      console.error("Failed to read download cache:", error)
+    }
+
+    this.dom.clearCacheButton.addEventListener("click", async () => {
+      try {
+        this.showCacheSize(await invoke<CacheSummary>("clear_download_cache"))
+        this.appendOutput("Download cache cleared.\n")
+      } catch (error) {
+        this.appendOutput(`Failed to clear download cache: ${formatInstallerError(error)}\n`)
+      }
+    })
+  }
+
+  private showCacheSize(summary: CacheSummary): void {
+    const megabytes = (summary.totalBytes / (1024 * 1024)).toFixed(1)
+    this.dom.clearCacheButton.textContent = `Clear download cache (${megabytes} MB)`
+    this.dom.clearCacheButton.title = summary.path
+  }
+
   private async checkForAppUpdates(): Promise<void> {
     try {
       const updateInfo = await this.updateManager.checkForUpdates()
@@ -380,10 +631,12 @@ class SpicetifyInstallerApp {
   }
 
   private initEventListeners(): void {
+    this.initReleaseChannel()
+    this.initDownloadCache()
+    this.initInstallerRollback()
+
     this.dom.executeButton.addEventListener("click", () => {
-      if (!this.isExecuting) {
-        this.executeCommand()
-      }
+      this.executeCommand()
     })
 
     this.dom.clearOutputButton.addEventListener("click", () => {
@@ -404,6 +657,106 @@ class SpicetifyInstallerApp {
       }
     })
 
+    this.dom.exportDiagnosticsButton.addEventListener("click", () => {
+      this.exportDiagnostics()
+    })
+
+    this.dom.themesButton.addEventListener("click", () => {
+      this.openThemesModal()
+    })
+
+    this.dom.closeThemesButton.addEventListener("click", () => {
+      this.closeThemesModal()
+    })
+
+    this.dom.themesModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.themesModal) {
+        this.closeThemesModal()
+      }
+    })
+
+    this.dom.extensionsButton.addEventListener("click", () => {
+      this.openExtensionsModal()
+    })
+
+    this.dom.closeExtensionsButton.addEventListener("click", () => {
+      this.closeExtensionsModal()
+    })
+
+    this.dom.extensionsModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.extensionsModal) {
+        this.closeExtensionsModal()
+      }
+    })
+
+    this.dom.installExtensionButton.addEventListener("click", () => {
+      this.installExtension()
+    })
+
+    this.dom.appsButton.addEventListener("click", () => {
+      this.openAppsModal()
+    })
+
+    this.dom.closeAppsButton.addEventListener("click", () => {
+      this.closeAppsModal()
+    })
+
+    this.dom.appsModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.appsModal) {
+        this.closeAppsModal()
+      }
+    })
+
+    this.dom.installAppButton.addEventListener("click", () => {
+      this.installApp()
+    })
+
+    this.dom.profilesButton.addEventListener("click", () => {
+      this.openProfilesModal()
+    })
+
+    this.dom.closeProfilesButton.addEventListener("click", () => {
+      this.closeProfilesModal()
+    })
+
+    this.dom.profilesModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.profilesModal) {
+        this.closeProfilesModal()
+      }
+    })
+
+    this.dom.saveProfileButton.addEventListener("click", () => {
+      this.saveProfile()
+    })
+
+    this.dom.configButton.addEventListener("click", () => {
+      this.openConfigModal()
+    })
+
+    this.dom.closeConfigButton.addEventListener("click", () => {
+      this.closeConfigModal()
+    })
+
+    this.dom.configModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.configModal) {
+        this.closeConfigModal()
+      }
+    })
+
+    this.dom.historyButton.addEventListener("click", () => {
+      this.openHistoryModal()
+    })
+
+    this.dom.closeHistoryButton.addEventListener("click", () => {
+      this.closeHistoryModal()
+    })
+
+    this.dom.historyModal.addEventListener("click", (event) => {
+      if (event.target === this.dom.historyModal) {
+        this.closeHistoryModal()
+      }
+    })
+
     this.dom.updateNotificationElement.addEventListener(
       "click",
       this.throttle(() => {
@@ -434,45 +787,48 @@ class SpicetifyInstallerApp {
     try {
       const locationInfo = await invoke<string>("check_spicetify_location")
       console.log("Spicetify location info:", locationInfo)
-
-      try {
-        const output = await invoke<string>("execute_powershell_command", {
-          command: "try { spicetify -v } catch { Write-Output 'Command failed' }",
-        })
-        console.log("Spicetify version command output:", output)
-      } catch (error) {
-        console.log("Failed to run spicetify command:", error)
-      }
     } catch (error) {
       console.error("Diagnosis error:", error)
     }
   }
 
   private selectOption(optionName: string, command: string): void {
+    const entry = SpicetifyInstallerApp.COMMANDS.find((c) => c.command === command || c.name === optionName)
     this.selectedCommand = command
+    this.selectedOperation = entry ? entry.operation : null
+    this.toggleVersionPicker(this.selectedOperation?.type === "install")
+    this.dom.archivePicker.classList.toggle("hidden", this.selectedOperation?.type !== "installFromArchive")
     this.dom.commandDisplay.textContent = `> ${command}`
     this.dom.executeButton.disabled = false
   }
 
-  private async executeCommand(): Promise<void> {
-    if (!this.selectedCommand || this.isExecuting) return
-
-    this.isExecuting = true
-
+  private showOutputCard(): void {
     this.dom.outputCard.classList.remove("hidden")
     requestAnimationFrame(() => {
       this.dom.outputCard.classList.add("visible")
     })
+  }
+
+  private async executeCommand(): Promise<void> {
+    if (this.isExecuting) {
+      await this.cancelOperation()
+      return
+    }
+    if (!this.selectedCommand || !this.selectedOperation) return
+
+    this.isExecuting = true
+
+    this.showOutputCard()
 
     this.currentProgress = 0
     this.updateProgressBar(0)
 
-    this.dom.executeButton.disabled = true
     this.dom.executeButton.classList.add("executing")
+    this.dom.executeButton.title = "Click to cancel"
     const originalButtonText = this.dom.executeButton.innerHTML
     this.dom.executeButton.innerHTML = `
       <div class="loading"></div>
-      <span>Executing...</span>
+      <span>Executing... (click to cancel)</span>
       <div class="button-progress-bar" id="progress-bar"></div>
       <div class="button-progress-percentage" id="progress-percentage">0%</div>
     `
@@ -484,13 +840,9 @@ class SpicetifyInstallerApp {
     this.appendOutput(`> ${this.selectedCommand}\n\n`)
 
     try {
-      this.setupProgressListener()
+      await this.setupProgressListener()
 
-      if (this.selectedCommand.includes("restore backup apply")) {
-        await this.executeFixedRepairCommand()
-      } else {
-        await this.executeCommandWithTauri(this.selectedCommand)
-      }
+      await this.executeOperationWithTauri(this.withOperationInputs(this.selectedOperation))
 
       this.completeProgress()
       this.appendOutput("\n<span class='success-text'>[SUCCESS] Command executed successfully!</span>\n")
@@ -498,9 +850,14 @@ class SpicetifyInstallerApp {
       await this.checkVersions()
     } catch (error) {
       this.completeProgress()
-      this.appendOutput(`\n<span class='error-text'>[ERROR] ${error}</span>\n`)
+      if (isInstallerError(error) && error.kind === "cancelled") {
+        this.appendOutput("\n<span class='warning-text'>[CANCELLED] Operation was cancelled.</span>\n")
+      } else {
+        this.appendOutput(`\n<span class='error-text'>[ERROR] ${formatInstallerError(error)}</span>\n`)
+      }
     } finally {
       this.dom.executeButton.innerHTML = originalButtonText
+      this.dom.executeButton.title = ""
       this.dom.executeButton.disabled = false
       this.dom.executeButton.classList.remove("executing")
       this.isExecuting = false
@@ -508,18 +865,12 @@ class SpicetifyInstallerApp {
     }
   }
 
-  private async executeFixedRepairCommand(): Promise<void> {
+  private async cancelOperation(): Promise<void> {
     try {
-      this.appendOutput("Step 1: Restoring from backup...\n")
-      await this.executeCommandWithTauri("spicetify restore")
-
-      this.appendOutput("\nStep 2: Creating a new backup...\n")
-      await this.executeCommandWithTauri("spicetify backup")
-
-      this.appendOutput("\nStep 3: Applying Spicetify customizations...\n")
-      await this.executeCommandWithTauri("spicetify apply")
+      await invoke<boolean>("cancel_operation")
+      this.appendOutput("\nCancelling...\n")
     } catch (error) {
-      throw new Error(`Repair process failed: ${error}`)
+      console.error("Error cancelling operation:", error)
     }
   }
 
@@ -528,6 +879,10 @@ class SpicetifyInstallerApp {
       const progress = event.payload as number
       this.updateProgressBar(progress)
     })
+    this.outputListener = await listen("operation_output", (event: any) => {
+      const { line } = event.payload as { stream: string; line: string }
+      this.appendOutput(`${line}\n`)
+    })
   }
 
   private removeProgressListener(): void {
@@ -535,24 +890,48 @@ class SpicetifyInstallerApp {
       this.progressListener()
       this.progressListener = null
     }
+    if (this.outputListener) {
+      this.outputListener()
+      this.outputListener = null
+    }
   }
 
-  private async executeCommandWithTauri(command: string): Promise<void> {
+  private async toggleVersionPicker(visible: boolean): Promise<void> {
+    this.dom.versionPicker.classList.toggle("hidden", !visible)
+    if (!visible || this.versionsLoaded) return
+
     try {
-      const output = await invoke<string>("execute_powershell_command", {
-        command,
-      })
-      this.appendOutput(`[PowerShell Output]\n${output}\n`)
+      const releases = await invoke<SpicetifyRelease[]>("list_spicetify_versions")
+      for (const release of releases) {
+        const option = document.createElement("option")
+        option.value = release.version
+        option.textContent = release.prerelease ? `${release.version} (prerelease)` : release.version
+        this.dom.versionSelect.appendChild(option)
+      }
+      this.versionsLoaded = true
     } catch (error) {
-      throw new Error(`${error}`)
+      this.appendOutput(`Could not load Spicetify versions: ${formatInstallerError(error)}\n`)
     }
   }
 
-  private completeProgress(): void {
-    if (this.progressInterval) {
-      clearInterval(this.progressInterval)
-      this.progressInterval = null
+  private withOperationInputs(operation: Operation): Operation {
+    if (operation.type === "install" && this.dom.versionSelect.value) {
+      return { type: "install", version: this.dom.versionSelect.value }
+    }
+    if (operation.type === "installFromArchive") {
+      return { type: "installFromArchive", archivePath: this.dom.archivePathInput.value.trim() }
     }
+    return operation
+  }
+
+  private async executeOperationWithTauri(operation: Operation): Promise<void> {
+    // Output is streamed line by line through the operation_output event.
+    await invoke<string>("execute_operation", {
+      operation,
+    })
+  }
+
+  private completeProgress(): void {
     this.updateProgressBar(100)
   }
 
@@ -622,6 +1001,487 @@ class SpicetifyInstallerApp {
     }, 300)
   }
 
+  private async openThemesModal(): Promise<void> {
+    this.dom.themesList.innerHTML = ""
+    this.dom.themesModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.themesModal.classList.add("visible")
+    })
+
+    try {
+      this.renderThemes(await invoke<ThemesOverview>("list_themes"))
+    } catch (error) {
+      this.dom.themesList.textContent = `Failed to load themes: ${formatInstallerError(error)}`
+    }
+  }
+
+  private renderThemes(overview: ThemesOverview): void {
+    this.dom.themesList.innerHTML = ""
+    if (overview.themes.length === 0) {
+      this.dom.themesList.innerHTML = "<li>No themes installed.</li>"
+      return
+    }
+
+    for (const theme of overview.themes) {
+      const active = theme.name === overview.currentTheme
+      const item = document.createElement("li")
+      item.className = `history-item theme-item${active ? " active" : ""}`
+
+      const name = document.createElement("span")
+      name.textContent = active ? `${theme.name} (active)` : theme.name
+      item.appendChild(name)
+
+      const schemes = document.createElement("select")
+      for (const scheme of theme.colorSchemes) {
+        const option = document.createElement("option")
+        option.value = scheme.name
+        option.textContent = scheme.name
+        option.selected = active && scheme.name === overview.colorScheme
+        schemes.appendChild(option)
+      }
+      schemes.classList.toggle("hidden", theme.colorSchemes.length === 0)
+      item.appendChild(schemes)
+
+      const useButton = document.createElement("button")
+      useButton.className = "faq-button"
+      useButton.textContent = "Apply"
+      useButton.addEventListener("click", () => this.setTheme(theme.name, schemes.value || null, useButton))
+      item.appendChild(useButton)
+
+      this.dom.themesList.appendChild(item)
+    }
+  }
+
+  private async setTheme(theme: string, colorScheme: string | null, button: HTMLButtonElement): Promise<void> {
+    button.disabled = true
+    try {
+      this.showOutputCard()
+      const overview = await invoke<ThemesOverview>("set_theme", { theme, colorScheme })
+      this.renderThemes(overview)
+      this.appendOutput(`Theme set to ${theme}${colorScheme ? ` (${colorScheme})` : ""}\n`)
+    } catch (error) {
+      this.appendOutput(`Failed to set theme: ${formatInstallerError(error)}\n`)
+    } finally {
+      button.disabled = false
+    }
+  }
+
+  private closeThemesModal(): void {
+    this.dom.themesModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.themesModal.classList.add("hidden")
+    }, 300)
+  }
+
+  private async openExtensionsModal(): Promise<void> {
+    this.dom.extensionsList.innerHTML = ""
+    this.dom.extensionsModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.extensionsModal.classList.add("visible")
+    })
+
+    try {
+      this.renderExtensions(await invoke<Extension[]>("list_extensions"))
+    } catch (error) {
+      this.dom.extensionsList.textContent = `Failed to load extensions: ${formatInstallerError(error)}`
+    }
+  }
+
+  private renderExtensions(extensions: Extension[]): void {
+    this.dom.extensionsList.innerHTML = ""
+    if (extensions.length === 0) {
+      this.dom.extensionsList.innerHTML = "<li>No extensions installed.</li>"
+      return
+    }
+
+    for (const extension of extensions) {
+      const item = document.createElement("li")
+      item.className = `history-item theme-item${extension.status === "missing" ? " failed" : ""}`
+
+      const name = document.createElement("span")
+      name.textContent = `${extension.name} (${extension.status}${extension.bundled ? ", bundled" : ""})`
+      item.appendChild(name)
+
+      const toggleButton = document.createElement("button")
+      toggleButton.className = "faq-button"
+      const enable = extension.status === "disabled"
+      toggleButton.textContent = enable ? "Enable" : "Disable"
+      toggleButton.addEventListener("click", () =>
+        this.updateExtensions(
+          "set_extension_enabled",
+          { name: extension.name, enabled: enable },
+          `${enable ? "Enabled" : "Disabled"} ${extension.name}`,
+          toggleButton,
+        ),
+      )
+      item.appendChild(toggleButton)
+
+      if (!extension.bundled && extension.path) {
+        const removeButton = document.createElement("button")
+        removeButton.className = "faq-button"
+        removeButton.textContent = "Remove"
+        removeButton.addEventListener("click", () =>
+          this.updateExtensions("remove_extension", { name: extension.name }, `Removed ${extension.name}`, removeButton),
+        )
+        item.appendChild(removeButton)
+      }
+
+      this.dom.extensionsList.appendChild(item)
+    }
+  }
+
+  private installExtension(): void {
+    const path = this.dom.extensionPathInput.value.trim()
+    if (!path) {
+      return
+    }
+    this.updateExtensions("install_extension", { path }, `Installed ${path}`, this.dom.installExtensionButton)
+  }
+
+  private async updateExtensions(
+    command: string,
+    args: Record<string, unknown>,
+    message: string,
+    button: HTMLButtonElement,
+  ): Promise<void> {
+    button.disabled = true
+    try {
+      this.showOutputCard()
+      this.renderExtensions(await invoke<Extension[]>(command, args))
+      this.appendOutput(`${message}\n`)
+    } catch (error) {
+      this.appendOutput(`Extension change failed: ${formatInstallerError(error)}\n`)
+    } finally {
+      button.disabled = false
+    }
+  }
+
+  private closeExtensionsModal(): void {
+    this.dom.extensionsModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.extensionsModal.classList.add("hidden")
+    }, 300)
+  }
+
+  private async openAppsModal(): Promise<void> {
+    this.dom.appsList.innerHTML = ""
+    this.dom.appsModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.appsModal.classList.add("visible")
+    })
+
+    try {
+      this.renderApps(await invoke<CustomApp[]>("list_custom_apps"))
+    } catch (error) {
+      this.dom.appsList.textContent = `Failed to load custom apps: ${formatInstallerError(error)}`
+    }
+  }
+
+  private renderApps(apps: CustomApp[]): void {
+    this.dom.appsList.innerHTML = ""
+    if (apps.length === 0) {
+      this.dom.appsList.innerHTML = "<li>No custom apps installed.</li>"
+      return
+    }
+
+    for (const app of apps) {
+      const item = document.createElement("li")
+      const broken = app.status === "missing" || app.problem !== null
+      item.className = `history-item theme-item${broken ? " failed" : ""}`
+
+      const name = document.createElement("span")
+      const label = app.displayName && app.displayName !== app.name ? `${app.displayName} [${app.name}]` : app.name
+      name.textContent = `${label} (${app.status}${app.bundled ? ", bundled" : ""})`
+      if (app.problem) {
+        name.title = app.problem
+      }
+      item.appendChild(name)
+
+      const toggleButton = document.createElement("button")
+      toggleButton.className = "faq-button"
+      const enable = app.status === "disabled"
+      toggleButton.textContent = enable ? "Enable" : "Disable"
+      toggleButton.disabled = enable && app.problem !== null
+      toggleButton.addEventListener("click", () =>
+        this.updateApps(
+          "set_custom_app_enabled",
+          { name: app.name, enabled: enable },
+          `${enable ? "Enabled" : "Disabled"} ${app.name}`,
+          toggleButton,
+        ),
+      )
+      item.appendChild(toggleButton)
+
+      if (!app.bundled && app.path) {
+        const removeButton = document.createElement("button")
+        removeButton.className = "faq-button"
+        removeButton.textContent = "Remove"
+        removeButton.addEventListener("click", () =>
+          this.updateApps("remove_custom_app", { name: app.name }, `Removed ${app.name}`, removeButton),
+        )
+        item.appendChild(removeButton)
+      }
+
+      this.dom.appsList.appendChild(item)
+    }
+  }
+
+  private installApp(): void {
+    const path = this.dom.appPathInput.value.trim()
+    if (!path) {
+      return
+    }
+    this.updateApps("install_custom_app", { path }, `Installed ${path}`, this.dom.installAppButton)
+  }
+
+  private async updateApps(
+    command: string,
+    args: Record<string, unknown>,
+    message: string,
+    button: HTMLButtonElement,
+  ): Promise<void> {
+    button.disabled = true
+    try {
+      this.showOutputCard()
+      this.renderApps(await invoke<CustomApp[]>(command, args))
+      this.appendOutput(`${message}\n`)
+    } catch (error) {
+      this.appendOutput(`Custom app change failed: ${formatInstallerError(error)}\n`)
+    } finally {
+      button.disabled = false
+    }
+  }
+
+  private closeAppsModal(): void {
+    this.dom.appsModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.appsModal.classList.add("hidden")
+    }, 300)
+  }
+
+  private async openProfilesModal(): Promise<void> {
+    this.dom.profilesList.innerHTML = ""
+    this.dom.profilesModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.profilesModal.classList.add("visible")
+    })
+
+    try {
+      this.renderProfiles(await invoke<SetupProfile[]>("list_profiles"))
+    } catch (error) {
+      this.dom.profilesList.textContent = `Failed to load profiles: ${formatInstallerError(error)}`
+    }
+  }
+
+  private renderProfiles(profiles: SetupProfile[]): void {
+    this.dom.profilesList.innerHTML = ""
+    if (profiles.length === 0) {
+      this.dom.profilesList.innerHTML = "<li>No profiles saved.</li>"
+      return
+    }
+
+    for (const profile of profiles) {
+      const item = document.createElement("li")
+      item.className = "history-item theme-item"
+
+      const name = document.createElement("span")
+      const theme = profile.currentTheme
+        ? `${profile.currentTheme}${profile.colorScheme ? ` / ${profile.colorScheme}` : ""}`
+        : "no theme"
+      name.textContent = `${profile.name} (${theme}, ${profile.extensions.length} extensions, ${profile.customApps.length} apps)`
+      name.title = `Saved ${new Date(profile.savedAt * 1000).toLocaleString()}`
+      item.appendChild(name)
+
+      const activateButton = document.createElement("button")
+      activateButton.className = "faq-button"
+      activateButton.textContent = "Activate"
+      activateButton.addEventListener("click", () => this.activateProfile(profile.name, activateButton))
+      item.appendChild(activateButton)
+
+      const deleteButton = document.createElement("button")
+      deleteButton.className = "faq-button"
+      deleteButton.textContent = "Delete"
+      deleteButton.addEventListener("click", () => this.deleteProfile(profile.name, deleteButton))
+      item.appendChild(deleteButton)
+
+      this.dom.profilesList.appendChild(item)
+    }
+  }
+
+  private async saveProfile(): Promise<void> {
+    const name = this.dom.profileNameInput.value.trim()
+    if (!name) {
+      return
+    }
+
+    this.dom.saveProfileButton.disabled = true
+    try {
+      this.renderProfiles(await invoke<SetupProfile[]>("save_profile", { name }))
+      this.dom.profileNameInput.value = ""
+    } catch (error) {
+      this.dom.profilesList.textContent = `Failed to save profile: ${formatInstallerError(error)}`
+    } finally {
+      this.dom.saveProfileButton.disabled = false
+    }
+  }
+
+  private async activateProfile(name: string, button: HTMLButtonElement): Promise<void> {
+    button.disabled = true
+    try {
+      this.showOutputCard()
+      await invoke<SetupProfile>("activate_profile", { name })
+      this.appendOutput(`Profile ${name} activated\n`)
+    } catch (error) {
+      this.appendOutput(`Failed to activate profile: ${formatInstallerError(error)}\n`)
+    } finally {
+      button.disabled = false
+    }
+  }
+
+  private async deleteProfile(name: string, button: HTMLButtonElement): Promise<void> {
+    button.disabled = true
+    try {
+      this.renderProfiles(await invoke<SetupProfile[]>("delete_profile", { name }))
+    } catch (error) {
+      this.dom.profilesList.textContent = `Failed to delete profile: ${formatInstallerError(error)}`
+      button.disabled = false
+    }
+  }
+
+  private closeProfilesModal(): void {
+    this.dom.profilesModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.profilesModal.classList.add("hidden")
+    }, 300)
+  }
+
+  private async openConfigModal(): Promise<void> {
+    this.dom.configSections.innerHTML = ""
+    this.dom.configModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.configModal.classList.add("visible")
+    })
+
+    try {
+      this.renderConfig(await invoke<SpicetifyConfig>("get_spicetify_config"))
+    } catch (error) {
+      this.dom.configSections.textContent = `Failed to load config: ${formatInstallerError(error)}`
+    }
+  }
+
+  private renderConfig(config: SpicetifyConfig): void {
+    this.dom.configSections.innerHTML = ""
+    for (const section of config.sections) {
+      const heading = document.createElement("h3")
+      heading.textContent = section.name
+      this.dom.configSections.appendChild(heading)
+
+      for (const entry of section.entries) {
+        const row = document.createElement("label")
+        row.className = "config-row"
+        row.textContent = entry.key
+
+        const input = document.createElement("input")
+        input.value = entry.value
+        input.addEventListener("change", () => this.setConfigValue(section.name, entry.key, input))
+        row.appendChild(input)
+        this.dom.configSections.appendChild(row)
+      }
+    }
+  }
+
+  private async setConfigValue(section: string, key: string, input: HTMLInputElement): Promise<void> {
+    input.disabled = true
+    try {
+      this.showOutputCard()
+      await invoke<SpicetifyConfig>("set_spicetify_config_value", {
+        section,
+        key,
+        value: input.value,
+        apply: this.dom.configAutoApply.checked,
+      })
+      this.appendOutput(`Set [${section}] ${key} = ${input.value}\n`)
+    } catch (error) {
+      this.appendOutput(`Failed to update ${key}: ${formatInstallerError(error)}\n`)
+    } finally {
+      input.disabled = false
+    }
+  }
+
+  private closeConfigModal(): void {
+    this.dom.configModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.configModal.classList.add("hidden")
+    }, 300)
+  }
+
+  private async exportDiagnostics(): Promise<void> {
+    this.dom.exportDiagnosticsButton.disabled = true
+    try {
+      const path = await invoke<string>("export_diagnostics")
+      this.showOutputCard()
+      this.appendOutput(`Diagnostics saved to ${path}\n`)
+    } catch (error) {
+      this.appendOutput(`Failed to export diagnostics: ${formatInstallerError(error)}\n`)
+    } finally {
+      this.dom.exportDiagnosticsButton.disabled = false
+    }
+  }
+
+  private async openHistoryModal(): Promise<void> {
+    this.dom.historyList.innerHTML = ""
+    this.dom.historyTranscript.classList.add("hidden")
+    this.dom.historyModal.classList.remove("hidden")
+    requestAnimationFrame(() => {
+      this.dom.historyModal.classList.add("visible")
+    })
+
+    try {
+      const history = await invoke<OperationSummary[]>("get_operation_history")
+      if (history.length === 0) {
+        this.dom.historyList.innerHTML = "<li>No operations recorded yet.</li>"
+        return
+      }
+      for (const entry of history) {
+        const item = document.createElement("li")
+        item.className = `history-item ${entry.status}`
+        const versions = [
+          entry.spicetifyVersion && `Spicetify ${entry.spicetifyVersion}`,
+          entry.spotifyVersion && `Spotify ${entry.spotifyVersion}`,
+        ]
+          .filter(Boolean)
+          .join(", ")
+        const exitCode = entry.exitCode === null ? "" : ` (exit ${entry.exitCode})`
+        item.textContent = `${new Date(entry.startedAt).toLocaleString()} · ${entry.operation} · ${entry.status}${exitCode}${
+          versions ? ` · ${versions}` : ""
+        }`
+        item.addEventListener("click", () => this.showTranscript(entry.id))
+        this.dom.historyList.appendChild(item)
+      }
+    } catch (error) {
+      this.dom.historyList.textContent = `Failed to load history: ${formatInstallerError(error)}`
+    }
+  }
+
+  private async showTranscript(id: string): Promise<void> {
+    try {
+      const record = await invoke<OperationRecord>("get_operation_transcript", { id })
+      const lines = record.transcript.map((entry) => entry.line)
+      if (record.error) lines.push(`Error: ${record.error}`)
+      this.dom.historyTranscript.textContent = lines.join("\n")
+    } catch (error) {
+      this.dom.historyTranscript.textContent = `Failed to load transcript: ${formatInstallerError(error)}`
+    }
+    this.dom.historyTranscript.classList.remove("hidden")
+  }
+
+  private closeHistoryModal(): void {
+    this.dom.historyModal.classList.remove("visible")
+    setTimeout(() => {
+      this.dom.historyModal.classList.add("hidden")
+    }, 300)
+  }
+
   private async checkVersions(): Promise<void> {
     try {
       const versionInfo = await invoke<VersionInfo>("check_versions")
@@ -632,6 +1492,34 @@ class SpicetifyInstallerApp {
       this.dom.spicetifyVersionElement.textContent = "Version check failed"
       this.dom.footerVersionElement.textContent = "v1.0.2-Alpha"
     }
+    await this.checkMarketplaceStatus()
+  }
+
+  private async checkMarketplaceStatus(): Promise<void> {
+    const element = this.dom.marketplaceStatusElement
+    try {
+      const status = await invoke<MarketplaceStatus>("get_marketplace_status")
+      element.className = "version-badge"
+      if (status.problem) {
+        element.textContent = "Marketplace needs attention"
+        element.title = status.problem
+        element.classList.add("error-text")
+      } else if (!status.installed) {
+        element.textContent = "Marketplace not installed"
+        element.title = "Choose MARKETPLACE to install it"
+        element.classList.add("warning-text")
+      } else {
+        element.textContent = `Marketplace ${status.version ? `v${status.version}` : "(unknown version)"}${status.enabled ? "" : " (disabled)"}`
+        element.title = status.updateAvailable
+          ? `Marketplace v${status.latestVersion} is available. Choose MARKETPLACE to update.`
+          : status.path ?? ""
+        element.classList.add(status.updateAvailable || !status.enabled ? "warning-text" : "success-text")
+      }
+    } catch (error) {
+      // Without Spicetify there is no config to inspect
+      console.error("Error checking Marketplace:", error)
+      element.className = "hidden"
+    }
   }
 
   private isVersionHigherThanLatest(currentVersion: string, latestVersion: string): boolean {
@@ -763,14 +1651,13 @@ class SpicetifyInstallerApp {
 
         console.log("Showing update dialog with info:", updateInfo)
         await this.updateManager.showUpdateDialog(updateInfo)
-      } else if (versionInfo.hasSpicetifyUpdate) {
-        // Handle Spicetify update by running install command
-        this.selectOption("INSTALL", SpicetifyInstallerApp.COMMANDS[0].command)
+      } else if (versionInfo.hasSpicetifyUpdate && !this.isExecuting) {
+        this.selectOption("UPGRADE", SpicetifyInstallerApp.COMMANDS[2].command)
         await this.executeCommand()
       }
     } catch (error) {
       console.error("Error handling update:", error)
-      this.appendOutput(`Error checking for updates: ${error}\n`)
+      this.appendOutput(`Error checking for updates: ${formatInstallerError(error)}\n`)
     }
   }
 
@@ -778,8 +1665,8 @@ class SpicetifyInstallerApp {
     try {
       const versionInfo = await invoke<VersionInfo>("check_versions")
 
-      if (versionInfo.hasSpicetifyUpdate) {
-        this.selectOption("INSTALL", SpicetifyInstallerApp.COMMANDS[0].command)
+      if (versionInfo.hasSpicetifyUpdate && !this.isExecuting) {
+        this.selectOption("UPGRADE", SpicetifyInstallerApp.COMMANDS[2].command)
         await this.executeCommand()
       }
     } catch (error) {
@@ -1,17 +1,31 @@
 import { invoke } from "@tauri-apps/api/core"
+import { formatInstallerError } from "./errors"
 import { listen } from "@tauri-apps/api/event"
 
+export type ReleaseChannel = "stable" | "beta" | "alpha"
+
 export interface UpdateInfo {
   currentVersion: string
   latestVersion: string
   downloadUrl: string
   updateAvailable: boolean
+  channel?: ReleaseChannel
 }
 
 export interface UpdateProgress {
   stage: string
   progress: number
   message: string
+  downloaded_bytes?: number
+  total_bytes?: number
+  bytes_per_second?: number
+}
+
+export interface InstallerVersion {
+  version: string
+  fileName: string
+  path: string
+  retainedAt: number
 }
 
 export class UpdateManager {
@@ -84,7 +98,9 @@ export class UpdateManager {
             </div>
             <div class="latest-version">
               <span class="version-label">Latest version:</span>
-              <span class="version-value">v${updateInfo.latestVersion}</span>
+              <span class="version-value">v${updateInfo.latestVersion}${
+                updateInfo.channel && updateInfo.channel !== "stable" ? ` (${updateInfo.channel})` : ""
+              }</span>
             </div>
           </div>
           <p class="update-benefits">
@@ -148,6 +164,23 @@ export class UpdateManager {
     })
   }
 
+  async listInstallerVersions(): Promise<InstallerVersion[]> {
+    try {
+      return await invoke<InstallerVersion[]>("list_installer_versions")
+    } catch (error) {
+      console.error("Failed to list installer versions:", error)
+      return []
+    }
+  }
+
+  async rollbackInstaller(version: string): Promise<void> {
+    try {
+      await invoke("rollback_installer", { version })
+    } catch (error) {
+      throw new Error(`Rollback failed: ${formatInstallerError(error)}`)
+    }
+  }
+
   private async downloadAndInstallUpdate(downloadUrl: string): Promise<void> {
     try {
       console.log("Starting update download and installation...")
@@ -160,7 +193,7 @@ export class UpdateManager {
       console.log("Update command completed:", result)
     } catch (error) {
       console.error("Update command failed:", error)
-      throw new Error(`Update failed: ${error}`)
+      throw new Error(`Update failed: ${formatInstallerError(error)}`)
     }
   }
 